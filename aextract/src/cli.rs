@@ -20,4 +20,8 @@ pub struct Cli {
     /// Output directory (defaults to creating a folder in the current directory.)
     #[arg(short = 'o', long = "output", value_parser = clap::value_parser!(std::path::PathBuf))]
     pub output: Option<PathBuf>,
+
+    /// Also export the generated data to a SQLite database at this path
+    #[arg(long = "sqlite", value_parser = clap::value_parser!(std::path::PathBuf))]
+    pub sqlite: Option<PathBuf>,
 }
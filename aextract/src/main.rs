@@ -9,7 +9,10 @@ use clap::Parser;
 use cli::Cli;
 use orbital_common::{
     steam::SteamLibrary,
-    types::satisfactory::{AssetReference, Generated, Generator, parse_docs_json},
+    types::satisfactory::{
+        AssetReference, DOCS_SCHEMA_FILE, Generated, Generator, docs_schema, export_sqlite, parse_docs_json,
+        validate_docs,
+    },
 };
 use serde_json::to_string_pretty;
 use tempfile::tempdir;
@@ -83,7 +86,20 @@ fn main() -> Result<(), Box<dyn Error>> {
     let parsed = parse_docs_json(steam.docs(), locale)?;
     let generated = Generator::new(parsed).generate();
 
+    validate_docs(&serde_json::to_value(&generated)?)?;
     fs::write(workdir.join("docs.json"), to_string_pretty(&generated)?)?;
+    fs::write(workdir.join(DOCS_SCHEMA_FILE), to_string_pretty(&docs_schema())?)?;
+    if let Some(database) = options.sqlite.as_ref() {
+        let summary = export_sqlite(&generated, database)?;
+        println!(
+            "Exported {} items, {} buildings, {} recipes and {} research to {}",
+            summary.items,
+            summary.buildings,
+            summary.recipes,
+            summary.research,
+            database.display()
+        );
+    }
     generate_asset_request(generated.clone(), workdir.clone())?;
 
     let ((exe_filename, exe_content), (lib_filename, lib_content)) = binaries();
@@ -129,6 +145,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     fs::create_dir_all(workdir.join("staging").join("map"))?;
     fs::create_dir_all(workdir.join("staging").join("icons"))?;
     fs::rename(workdir.join("docs.json"), workdir.join("staging").join("docs.json"))?;
+    fs::rename(workdir.join(DOCS_SCHEMA_FILE), workdir.join("staging").join(DOCS_SCHEMA_FILE))?;
 
     for fp in glob::glob(workdir.join("assets").join("*.png").to_str().unwrap())? {
         if let Ok(pt) = fp {
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use orbital_common::types::satisfactory::{OrbitalItemKind, PlanTarget};
+
+#[derive(Parser, Clone, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// Asset pack to read, either a docs.json or an assets.zip produced by aextract
+    #[arg(short, long, global = true, default_value = "./extracted/assets.zip", value_parser = clap::value_parser!(std::path::PathBuf))]
+    pub pack: PathBuf,
+
+    /// Output format
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Table)]
+    pub format: Format,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Show an item or building, with the recipes that produce and use it
+    Item {
+        /// Id or display name
+        query: String,
+    },
+
+    /// Show a recipe's ingredients, products and machine
+    Recipe {
+        /// Id or display name
+        query: String,
+    },
+
+    /// Search items, buildings, recipes and research by name
+    Search {
+        query: String,
+
+        /// Only return results of these kinds
+        #[arg(short, long, value_enum)]
+        kind: Vec<Kind>,
+
+        /// Maximum number of results
+        #[arg(short, long, default_value_t = 20)]
+        limit: u32,
+    },
+
+    /// Plan the machines and raw resources needed for a set of production targets
+    Plan {
+        /// Targets as ITEM:RATE[/UNIT], e.g. "Computer:10/min". Units are s, min or h.
+        #[arg(required = true)]
+        targets: Vec<PlanTarget>,
+
+        /// Use a specific recipe for an item, as ITEM=RECIPE
+        #[arg(short, long = "recipe", value_parser = parse_override)]
+        recipes: Vec<(String, String)>,
+
+        /// Fall back to alternate recipes for items that no standard recipe makes
+        #[arg(short, long)]
+        alternates: bool,
+
+        /// Treat an item as a raw input instead of producing it
+        #[arg(long)]
+        raw: Vec<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Markdown,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Research,
+    Description,
+    Buildable,
+    Recipe,
+}
+
+impl From<Kind> for OrbitalItemKind {
+    fn from(value: Kind) -> Self {
+        match value {
+            Kind::Research => OrbitalItemKind::Research,
+            Kind::Description => OrbitalItemKind::Description,
+            Kind::Buildable => OrbitalItemKind::Buildable,
+            Kind::Recipe => OrbitalItemKind::Recipe,
+        }
+    }
+}
+
+fn parse_override(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(item, recipe)| (item.trim().to_string(), recipe.trim().to_string()))
+        .filter(|(item, recipe)| !item.is_empty() && !recipe.is_empty())
+        .ok_or(format!("Expected ITEM=RECIPE, got {value:?}"))
+}
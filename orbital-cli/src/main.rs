@@ -0,0 +1,410 @@
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+use clap::Parser;
+use cli::{Cli, Command};
+use orbital_common::{
+    CommonError, PlanError,
+    types::satisfactory::{
+        DataIndex, DescriptionType, ItemReference, OrbitalData, ItemRate, OrbitalItem, PlanOptions, PlanTarget, Planner, RecipeItem,
+        SearchFilters, SearchIndex,
+    },
+};
+use output::{Table, number, render};
+use serde::Serialize;
+
+mod cli;
+mod output;
+mod pack;
+
+/// Exit codes, so scripts can tell a failed lookup from a broken pack. Usage errors exit with clap's code 2.
+const EXIT_NOT_FOUND: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_LOAD_FAILED: u8 = 3;
+const EXIT_PLAN_FAILED: u8 = 4;
+
+#[derive(Serialize, Clone, Debug)]
+struct RecipeSummary {
+    id: String,
+    display_name: String,
+    machine: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct ItemReport {
+    item: OrbitalItem,
+    produced_by: Vec<RecipeSummary>,
+    used_by: Vec<RecipeSummary>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct RecipeAmount {
+    item: String,
+    display_name: String,
+    amount: f32,
+    per_minute: f32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct RecipeReport {
+    id: String,
+    display_name: String,
+    duration: f32,
+    machines: Vec<String>,
+    ingredients: Vec<RecipeAmount>,
+    products: Vec<RecipeAmount>,
+}
+
+/// Result of a command: the text to print, or the exit code and message of a failure.
+type Outcome = Result<String, (u8, String)>;
+
+fn display_name(data: &OrbitalData, id: &str) -> String {
+    data.get_id(id.to_string())
+        .map_or(id.to_string(), |item| item.display_name().to_string())
+}
+
+/// Fluid amounts are stored in liters and shown in cubic meters, as in game.
+fn scaled_amount(data: &OrbitalData, reference: &ItemReference) -> f32 {
+    let amount = reference.amount.as_f32().unwrap_or(0.0);
+    match data.descriptions.get(reference.item.id()).map(|item| &item.description_type) {
+        Some(DescriptionType::Liquid | DescriptionType::Gas) => amount / 1000.0,
+        _ => amount,
+    }
+}
+
+fn machines(recipe: &RecipeItem) -> Vec<String> {
+    recipe
+        .machine
+        .as_ref()
+        .iter()
+        .flatten()
+        .map(|machine| machine.id().to_string())
+        .collect()
+}
+
+fn summarize(data: &OrbitalData, recipes: Vec<RecipeItem>) -> Vec<RecipeSummary> {
+    let mut summaries: Vec<RecipeSummary> = recipes
+        .iter()
+        .map(|recipe| RecipeSummary {
+            id: recipe.id.as_ref().to_string(),
+            display_name: recipe.display_name.clone(),
+            machine: machines(recipe)
+                .iter()
+                .find(|machine| data.buildables.contains_key(machine.as_str()))
+                .map(|machine| display_name(data, machine)),
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    summaries
+}
+
+/// Finds an entry by id, or by display name ignoring case.
+fn lookup<'a, T>(entries: impl Iterator<Item = (&'a String, &'a T)>, name: impl Fn(&T) -> &str, query: &str) -> Option<&'a T> {
+    let mut matches: Vec<(&String, &T)> = entries
+        .filter(|(id, entry)| id.as_str() == query || name(entry).eq_ignore_ascii_case(query))
+        .collect();
+    matches.sort_by_key(|(id, _)| (id.as_str() != query, id.as_str()));
+    matches.first().map(|(_, entry)| *entry)
+}
+
+fn item(data: &OrbitalData, query: &str, cli: &Cli) -> Outcome {
+    let item = lookup(data.descriptions.iter(), |item| item.display_name.as_str(), query)
+        .cloned()
+        .map(OrbitalItem::Description)
+        .or_else(|| {
+            lookup(data.buildables.iter(), |item| item.display_name.as_str(), query)
+                .cloned()
+                .map(OrbitalItem::Buildable)
+        })
+        .ok_or((EXIT_NOT_FOUND, format!("No item or building matches {query:?}")))?;
+
+    let index = DataIndex::build(data);
+    let report = ItemReport {
+        produced_by: summarize(data, index.recipes_for(data, item.id())),
+        used_by: summarize(data, index.recipes_using(data, item.id())),
+        item,
+    };
+
+    let mut details = Table::new(report.item.display_name(), &["Field", "Value"])
+        .row(["Id", report.item.id()])
+        .row(["Name", report.item.display_name()]);
+    match &report.item {
+        OrbitalItem::Description(description) => {
+            details = details.row(["Type", &format!("{:?}", description.description_type)]);
+            if let Some(points) = description.resource_sink_points.as_ref().and_then(|points| points.as_f32()) {
+                details = details.row(["Sink points", &number(points)]);
+            }
+        }
+        OrbitalItem::Buildable(building) => {
+            if let Some(power) = building.power_consumption.as_ref().and_then(|power| power.as_f32()) {
+                details = details.row(["Power (MW)", &number(power)]);
+            }
+        }
+        _ => (),
+    }
+
+    let recipes = |title: &str, summaries: &Vec<RecipeSummary>| {
+        Table::new(title, &["Recipe", "Name", "Machine"]).rows(summaries, |recipe| {
+            vec![recipe.id.clone(), recipe.display_name.clone(), recipe.machine.clone().unwrap_or_default()]
+        })
+    };
+    Ok(render(
+        cli.format,
+        &report,
+        &[details, recipes("Produced by", &report.produced_by), recipes("Used by", &report.used_by)],
+    ))
+}
+
+fn recipe(data: &OrbitalData, query: &str, cli: &Cli) -> Outcome {
+    let recipe = lookup(data.recipes.iter(), |recipe| recipe.display_name.as_str(), query)
+        .ok_or((EXIT_NOT_FOUND, format!("No recipe matches {query:?}")))?;
+
+    let duration = recipe.duration.as_f32().unwrap_or(0.0);
+    let amounts = |references: &Option<Vec<ItemReference>>| -> Vec<RecipeAmount> {
+        references
+            .iter()
+            .flatten()
+            .map(|reference| {
+                let amount = scaled_amount(data, reference);
+                RecipeAmount {
+                    item: reference.item.id().to_string(),
+                    display_name: display_name(data, reference.item.id()),
+                    amount,
+                    per_minute: if duration > 0.0 { amount * 60.0 / duration } else { 0.0 },
+                }
+            })
+            .collect()
+    };
+    let report = RecipeReport {
+        id: recipe.id.as_ref().to_string(),
+        display_name: recipe.display_name.clone(),
+        duration,
+        machines: machines(recipe),
+        ingredients: amounts(recipe.ingredients.as_ref()),
+        products: amounts(recipe.product.as_ref()),
+    };
+
+    let machine_names: Vec<String> = report.machines.iter().map(|machine| display_name(data, machine)).collect();
+    let details = Table::new(&report.display_name, &["Field", "Value"])
+        .row(["Id", &report.id])
+        .row(["Name", &report.display_name])
+        .row(["Duration (s)", &number(report.duration)])
+        .row(["Machines", &machine_names.join(", ")]);
+    let amounts = |title: &str, amounts: &Vec<RecipeAmount>| {
+        Table::new(title, &["Item", "Name", "Amount", "Per minute"]).rows(amounts, |amount| {
+            vec![
+                amount.item.clone(),
+                amount.display_name.clone(),
+                number(amount.amount),
+                number(amount.per_minute),
+            ]
+        })
+    };
+    Ok(render(
+        cli.format,
+        &report,
+        &[details, amounts("Ingredients", &report.ingredients), amounts("Products", &report.products)],
+    ))
+}
+
+fn search(data: &OrbitalData, query: &str, filters: SearchFilters, cli: &Cli) -> Outcome {
+    let results = SearchIndex::build(data).search(query, &filters);
+    if results.is_empty() {
+        return Err((EXIT_NOT_FOUND, format!("Nothing matches {query:?}")));
+    }
+
+    let table = Table::new(format!("Results for {query:?}"), &["Id", "Kind", "Name", "Score"]).rows(&results, |result| {
+        vec![
+            result.id.clone(),
+            format!("{:?}", result.kind).to_lowercase(),
+            result.display_name.clone(),
+            number(result.score),
+        ]
+    });
+    Ok(render(cli.format, &results, &[table]))
+}
+
+fn plan(
+    data: &OrbitalData,
+    targets: &[PlanTarget],
+    recipes: &[(String, String)],
+    alternates: bool,
+    raw: &[String],
+    cli: &Cli,
+) -> Outcome {
+    let planner = Planner::new(data, PlanOptions::default());
+    let resolve = |item: &String| planner.resolve_item(item).map_err(CommonError::from);
+    let options = PlanOptions {
+        recipes: recipes
+            .iter()
+            .map(|(item, recipe)| Ok((resolve(item)?, recipe.clone())))
+            .collect::<orbital_common::Result<_>>()
+            .map_err(plan_failure)?,
+        alternates,
+        raw: raw
+            .iter()
+            .map(resolve)
+            .collect::<orbital_common::Result<_>>()
+            .map_err(plan_failure)?,
+    };
+    let plan = Planner::new(data, options).plan(targets).map_err(plan_failure)?;
+
+    let rates = |title: &str, rates: &Vec<ItemRate>| {
+        Table::new(title, &["Item", "Name", "Per minute"]).rows(rates, |rate| {
+            vec![rate.item.clone(), rate.display_name.clone(), number(rate.rate)]
+        })
+    };
+    let steps = Table::new("Steps", &["Recipe", "Machine", "Count", "Power (MW)", "Outputs per minute"]).rows(
+        &plan.steps,
+        |step| {
+            vec![
+                step.recipe_name.clone(),
+                step.machine_name.clone().unwrap_or_default(),
+                number(step.machines),
+                number(step.power),
+                step.outputs
+                    .iter()
+                    .map(|output| format!("{} {}", number(output.rate), output.display_name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ]
+        },
+    );
+    let summary = Table::new("Summary", &["Field", "Value"])
+        .row(["Machines", &number(plan.steps.iter().map(|step| step.machines.ceil()).sum())])
+        .row(["Power (MW)", &number(plan.power)]);
+
+    Ok(render(
+        cli.format,
+        &plan,
+        &[
+            rates("Targets", &plan.targets),
+            steps,
+            rates("Raw inputs", &plan.raw_inputs),
+            rates("Byproducts", &plan.byproducts),
+            summary,
+        ],
+    ))
+}
+
+fn plan_failure(error: CommonError) -> (u8, String) {
+    let code = match &error {
+        CommonError::Plan {
+            error: PlanError::UnknownItem { .. } | PlanError::UnknownRecipe { .. },
+        } => EXIT_NOT_FOUND,
+        CommonError::Plan {
+            error: PlanError::InvalidTarget { .. },
+        } => EXIT_USAGE,
+        _ => EXIT_PLAN_FAILED,
+    };
+    (code, error.to_string())
+}
+
+/// Runs a parsed command line, returning what to print, or the exit code and message of a failure.
+fn run(options: &Cli) -> Outcome {
+    let data = pack::load(&options.pack)
+        .map_err(|e| (EXIT_LOAD_FAILED, format!("Failed to load {}: {e}", options.pack.display())))?;
+
+    match &options.command {
+        Command::Item { query } => item(&data, query, options),
+        Command::Recipe { query } => recipe(&data, query, options),
+        Command::Search { query, kind, limit } => search(
+            &data,
+            query,
+            SearchFilters {
+                kinds: kind.iter().map(|kind| (*kind).into()).collect(),
+                limit: Some(*limit),
+                ..Default::default()
+            },
+            options,
+        ),
+        Command::Plan {
+            targets,
+            recipes,
+            alternates,
+            raw,
+        } => plan(&data, targets, recipes, *alternates, raw, options),
+    }
+}
+
+fn main() -> ExitCode {
+    match run(&Cli::parse()) {
+        Ok(output) => {
+            // A closed pipe (e.g. `| head`) is not an error for the caller.
+            let _ = writeln!(io::stdout(), "{output}");
+            ExitCode::SUCCESS
+        }
+        Err((code, message)) => {
+            eprintln!("{message}");
+            ExitCode::from(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    const DOCS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/assets/1.0-stable/docs.json");
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(["orbital-cli", "--pack", DOCS, "--format", "json"].iter().chain(args))
+    }
+
+    /// Runs a command against the bundled 1.0 docs, returning its JSON output or its exit code.
+    fn json(args: &[&str]) -> Result<Value, u8> {
+        run(&parse(args).unwrap()).map(|output| serde_json::from_str(&output).unwrap()).map_err(|(code, _)| code)
+    }
+
+    #[test]
+    fn item_output() {
+        let report = json(&["item", "iron plate"]).unwrap();
+        assert_eq!(report["item"]["id"], "DescIronPlateC");
+        assert_eq!(report["item"]["display_name"], "Iron Plate");
+        let produced_by = report["produced_by"].as_array().unwrap();
+        assert!(produced_by.iter().any(|recipe| recipe["id"] == "RecipeIronPlateC" && recipe["machine"] == "Constructor"));
+        assert!(report["used_by"].as_array().unwrap().iter().any(|recipe| recipe["id"] == "RecipeIronPlateReinforcedC"));
+
+        assert_eq!(json(&["item", "DescIronPlateC"]).unwrap()["item"], report["item"]);
+        assert_eq!(json(&["item", "Not An Item"]), Err(EXIT_NOT_FOUND));
+    }
+
+    #[test]
+    fn search_output() {
+        let results = json(&["search", "iron plate", "--kind", "recipe", "--limit", "3"]).unwrap();
+        let results = results.as_array().unwrap();
+        assert!(!results.is_empty() && results.len() <= 3);
+        assert!(results.iter().all(|result| result["kind"] == "recipe"));
+        assert_eq!(results[0]["id"], "RecipeIronPlateC");
+
+        assert_eq!(json(&["search", "zzzzqqqq"]), Err(EXIT_NOT_FOUND));
+    }
+
+    #[test]
+    fn plan_output() {
+        let plan = json(&["plan", "Iron Plate:20/min"]).unwrap();
+        assert_eq!(plan["targets"][0]["item"], "DescIronPlateC");
+        assert_eq!(plan["targets"][0]["rate"], 20.0);
+        let steps: Vec<&Value> = plan["steps"].as_array().unwrap().iter().collect();
+        assert_eq!(steps.iter().map(|step| step["recipe"].as_str().unwrap()).collect::<Vec<_>>(), ["RecipeIngotIronC", "RecipeIronPlateC"]);
+        assert_eq!(plan["raw_inputs"][0]["item"], "DescOreIronC");
+        assert_eq!(plan["raw_inputs"][0]["rate"], 30.0);
+
+        assert_eq!(json(&["plan", "Not An Item:1/min"]), Err(EXIT_NOT_FOUND));
+        assert_eq!(json(&["plan", "Iron Plate:1", "--recipe", "Iron Plate=NotARecipe"]), Err(EXIT_NOT_FOUND));
+    }
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(parse(&["plan", "Iron Plate:1/week"]).unwrap_err().exit_code(), EXIT_USAGE as i32);
+        assert_eq!(parse(&["plan"]).unwrap_err().exit_code(), EXIT_USAGE as i32);
+        assert_eq!(parse(&["unknown"]).unwrap_err().exit_code(), EXIT_USAGE as i32);
+
+        let missing = Cli::try_parse_from(["orbital-cli", "--pack", "/nonexistent/docs.json", "item", "Iron Plate"]).unwrap();
+        assert_eq!(run(&missing).map_err(|(code, _)| code), Err(EXIT_LOAD_FAILED));
+    }
+}
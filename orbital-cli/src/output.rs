@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+use crate::cli::Format;
+
+/// A titled table printed by the table and markdown formats. JSON output serializes the command's result instead.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(title: impl AsRef<str>, headers: &[&str]) -> Self {
+        Self {
+            title: title.as_ref().to_string(),
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(mut self, cells: impl IntoIterator<Item = impl ToString>) -> Self {
+        self.rows.push(cells.into_iter().map(|cell| cell.to_string()).collect());
+        self
+    }
+
+    pub fn rows<T>(self, items: impl IntoIterator<Item = T>, cells: impl Fn(T) -> Vec<String>) -> Self {
+        items.into_iter().fold(self, |table, item| table.row(cells(item)))
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(column, header)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .chain(std::iter::once(header))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn render_text(&self) -> String {
+        let widths = self.widths();
+        let line = |cells: &[String]| {
+            widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| format!("{:<width$}", cells.get(column).map_or("", String::as_str)))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![self.title.clone(), line(&self.headers)];
+        lines.push(widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("  "));
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+
+    fn render_markdown(&self) -> String {
+        let line = |cells: &[String]| {
+            let cells: Vec<String> = (0..self.headers.len())
+                .map(|column| cells.get(column).map_or(String::new(), |cell| cell.replace('|', "\\|")))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut lines = vec![format!("## {}", self.title), String::new(), line(&self.headers)];
+        lines.push(format!("|{}|", vec!["---"; self.headers.len()].join("|")));
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+/// Renders a command's result. Tables without rows are left out of the table and markdown formats.
+pub fn render(format: Format, value: &impl Serialize, tables: &[Table]) -> String {
+    let tables = tables.iter().filter(|table| !table.rows.is_empty());
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).unwrap_or_default(),
+        Format::Table => tables.map(Table::render_text).collect::<Vec<_>>().join("\n\n"),
+        Format::Markdown => tables.map(Table::render_markdown).collect::<Vec<_>>().join("\n\n"),
+    }
+}
+
+/// Formats a rate or amount without trailing zeros.
+pub fn number(value: f32) -> String {
+    let formatted = format!("{value:.3}");
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
@@ -0,0 +1,47 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use orbital_common::{
+    CommonError, DocsError,
+    types::satisfactory::{Generated, OrbitalData},
+};
+use zip::ZipArchive;
+
+/// Reads the generated data of an asset pack. `path` is either the pack's `docs.json` or the zip archive that
+/// aextract writes, which holds `docs.json` at its root.
+pub fn load(path: impl AsRef<Path>) -> orbital_common::Result<OrbitalData> {
+    let path = path.as_ref();
+    let raw = if path.extension().is_some_and(|extension| extension == "zip") {
+        read_archive(path)?
+    } else {
+        fs::read(path).map_err(|e| DocsError::failed_read(path, e))?
+    };
+
+    let generated = serde_json::from_slice::<Generated>(raw.as_slice())
+        .map_err(|e| DocsError::invalid_format(format!("Bad JSON data: {e}")))?;
+    Ok(OrbitalData::from(generated))
+}
+
+fn read_archive(path: &Path) -> orbital_common::Result<Vec<u8>> {
+    let file = fs::File::open(path).map_err(|e| DocsError::failed_read(path, e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| DocsError::invalid_format(format!("Bad pack archive: {e}")))?;
+
+    for name in ["docs.json", "./docs.json"] {
+        if let Ok(mut entry) = archive.by_name(name) {
+            let mut buf: Vec<u8> = Vec::new();
+            entry
+                .read_to_end(&mut buf)
+                .map_err(|e| DocsError::failed_read(path.join(name), e))?;
+            return Ok(buf);
+        }
+    }
+
+    Err(CommonError::from(DocsError::failed_read(
+        path.join("docs.json"),
+        io::Error::from(io::ErrorKind::NotFound),
+    )))
+}
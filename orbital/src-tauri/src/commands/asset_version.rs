@@ -2,9 +2,20 @@ use std::fs;
 
 use tauri::{AppHandle, Manager, Runtime};
 
-#[taurpc::procedures(path = "asset_versions")]
+use super::settings::forget_default_asset_version;
+use crate::utils::{AssetInstallReport, AssetSource, AssetsExt, GameData, PackArchives, ProjectsExt};
+
+#[taurpc::procedures(path = "asset_versions", event_trigger = AssetVersionsEventTrigger)]
 pub trait AssetVersionsApi {
     async fn list_available(app_handle: AppHandle<impl Runtime>) -> crate::Result<Vec<String>>;
+    async fn install(app_handle: AppHandle, path: String, name: String) -> crate::Result<AssetInstallReport>;
+    async fn uninstall(app_handle: AppHandle, name: String) -> crate::Result<()>;
+
+    #[taurpc(event)]
+    async fn installed(report: AssetInstallReport);
+
+    #[taurpc(event)]
+    async fn uninstalled(name: String);
 }
 
 #[derive(Clone)]
@@ -13,26 +24,49 @@ pub struct AssetVersionsImpl;
 #[taurpc::resolvers]
 impl AssetVersionsApi for AssetVersionsImpl {
     async fn list_available(self, app_handle: AppHandle<impl Runtime>) -> crate::Result<Vec<String>> {
-        let asset_dir = app_handle.path().resource_dir().or_else(|e| Err(crate::Error::from(e)))?.join("resources/assets");
-        if !asset_dir.exists() {
-            return Err(crate::OperationError::invalid_path(asset_dir.as_path(), crate::InvalidPathType::NotExists));
+        app_handle.asset_versions()
+    }
+
+    async fn install(self, app_handle: AppHandle, path: String, name: String) -> crate::Result<AssetInstallReport> {
+        let handle = app_handle.clone();
+        let report = tokio::task::spawn_blocking(move || handle.install_asset_version(path, name))
+            .await
+            .or_else(|e| Err(crate::Error::from(tauri::Error::JoinError(e))))??;
+        app_handle.state::<GameData>().evict(&report.name).await;
+
+        AssetVersionsEventTrigger::new(app_handle.clone()).installed(report.clone())?;
+        Ok(report)
+    }
+
+    async fn uninstall(self, app_handle: AppHandle, name: String) -> crate::Result<()> {
+        if app_handle.is_bundled_asset_version(name.clone()) {
+            return Err(crate::AssetVersionError::bundled(name));
         }
-        if !asset_dir.is_dir() {
-            return Err(crate::OperationError::invalid_path(asset_dir.as_path(), crate::InvalidPathType::ExpectedFolder));
+
+        let source = app_handle.asset_source(name.clone())?;
+
+        let users: Vec<String> = app_handle
+            .list_projects()
+            .await?
+            .into_iter()
+            .filter(|project| project.asset_version == name)
+            .map(|project| project.name)
+            .collect();
+        if !users.is_empty() {
+            return Err(crate::AssetVersionError::in_use(name, users));
         }
 
-        let read_dir = fs::read_dir(asset_dir).or_else(|e| Err(crate::Error::from(e)))?;
-
-        Ok(read_dir.filter_map(|entry| {
-            if let Ok(e) = entry {
-                if e.path().is_dir() {
-                    Some(e.file_name().into_string().unwrap())
-                } else {
-                    None
-                }
-            } else {
-                None
+        match source {
+            AssetSource::Directory(path) => fs::remove_dir_all(path),
+            AssetSource::Archive(path) => {
+                app_handle.state::<PackArchives>().forget(&path);
+                fs::remove_file(path)
             }
-        }).collect())
+        }
+        .or_else(|e| Err(crate::Error::from(e)))?;
+        app_handle.state::<GameData>().evict(&name).await;
+        forget_default_asset_version(&app_handle, name.clone()).await?;
+        AssetVersionsEventTrigger::new(app_handle.clone()).uninstalled(name)?;
+        Ok(())
     }
 }
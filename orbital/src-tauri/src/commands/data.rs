@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use orbital_common::types::satisfactory::{
+    OrbitalItem, OrbitalItemKind, RecipeItem, SearchFilters, SearchResult,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Window};
+
+use crate::utils::{DataLoadStage, GameDataExt, LoadedData};
+
+use super::projects::ProjectsImpl;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct DataLoadProgress {
+    pub version: String,
+
+    #[serde(flatten)]
+    pub stage: DataLoadStage,
+}
+
+#[taurpc::procedures(path = "data", event_trigger = DataEventTrigger)]
+pub trait DataApi {
+    async fn get(app_handle: AppHandle, window: Window, id: String) -> crate::Result<Option<OrbitalItem>>;
+    async fn list_by_kind(app_handle: AppHandle, window: Window, kind: OrbitalItemKind) -> crate::Result<Vec<OrbitalItem>>;
+    async fn recipes_for(app_handle: AppHandle, window: Window, item: String) -> crate::Result<Vec<RecipeItem>>;
+    async fn search(app_handle: AppHandle, window: Window, query: String, filters: SearchFilters) -> crate::Result<Vec<SearchResult>>;
+
+    #[taurpc(event)]
+    async fn load_progress(progress: DataLoadProgress);
+}
+
+/// Loads (or fetches from cache) the data for `version`, reporting progress to every window.
+pub async fn load_data(app_handle: AppHandle, version: String) -> crate::Result<Arc<LoadedData>> {
+    let events = app_handle.clone();
+    let reported = version.clone();
+    app_handle
+        .game_data(
+            version,
+            Arc::new(move |stage| {
+                let _ = DataEventTrigger::new(events.clone()).load_progress(DataLoadProgress {
+                    version: reported.clone(),
+                    stage,
+                });
+            }),
+        )
+        .await
+}
+
+/// Starts loading the data for `version` in the background, so it's ready by the time the UI asks for it.
+pub fn preload_data(app_handle: AppHandle, version: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = load_data(app_handle, version.clone()).await {
+            log::error!("Failed to load game data for {version}: {e}");
+        }
+    });
+}
+
+#[derive(Clone)]
+pub struct DataImpl {
+    projects: ProjectsImpl,
+}
+
+impl DataImpl {
+    pub fn new(projects: ProjectsImpl) -> Self {
+        Self { projects }
+    }
+
+    async fn active_data(&self, app_handle: AppHandle, window: &Window) -> crate::Result<Arc<LoadedData>> {
+        let config = self.projects.active_project(window.label()).await?.config();
+        load_data(app_handle, config.asset_version).await
+    }
+}
+
+#[taurpc::resolvers]
+impl DataApi for DataImpl {
+    async fn get(self, app_handle: AppHandle, window: Window, id: String) -> crate::Result<Option<OrbitalItem>> {
+        Ok(self.active_data(app_handle, &window).await?.data.get_id(id))
+    }
+
+    async fn list_by_kind(self, app_handle: AppHandle, window: Window, kind: OrbitalItemKind) -> crate::Result<Vec<OrbitalItem>> {
+        Ok(self.active_data(app_handle, &window).await?.data.list_by_kind(kind))
+    }
+
+    async fn recipes_for(self, app_handle: AppHandle, window: Window, item: String) -> crate::Result<Vec<RecipeItem>> {
+        let loaded = self.active_data(app_handle, &window).await?;
+        Ok(loaded.index.recipes_for(&loaded.data, item))
+    }
+
+    async fn search(self, app_handle: AppHandle, window: Window, query: String, filters: SearchFilters) -> crate::Result<Vec<SearchResult>> {
+        Ok(self.active_data(app_handle, &window).await?.search.search(query, &filters))
+    }
+}
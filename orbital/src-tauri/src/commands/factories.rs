@@ -0,0 +1,156 @@
+use bson::doc;
+use tauri::Window;
+
+use crate::utils::{
+    Factory, FactoryCreationModel, LineLink, LineLinkCreationModel, Note, NoteCreationModel,
+    ProductionLine, ProductionLineCreationModel,
+};
+
+use super::projects::ProjectsImpl;
+
+#[taurpc::procedures(path = "factories")]
+pub trait FactoriesApi {
+    async fn list_factories(window: Window) -> crate::Result<Vec<Factory>>;
+    async fn create_factory(window: Window, model: FactoryCreationModel) -> crate::Result<Factory>;
+    async fn update_factory(window: Window, factory: Factory) -> crate::Result<Factory>;
+    async fn remove_factory(window: Window, id: String) -> crate::Result<Factory>;
+
+    async fn list_lines(window: Window, factory_id: String) -> crate::Result<Vec<ProductionLine>>;
+    async fn create_line(window: Window, model: ProductionLineCreationModel) -> crate::Result<ProductionLine>;
+    async fn update_line(window: Window, line: ProductionLine) -> crate::Result<ProductionLine>;
+    async fn remove_line(window: Window, id: String) -> crate::Result<ProductionLine>;
+
+    async fn list_links(window: Window, factory_id: String) -> crate::Result<Vec<LineLink>>;
+    async fn create_link(window: Window, model: LineLinkCreationModel) -> crate::Result<LineLink>;
+    async fn update_link(window: Window, link: LineLink) -> crate::Result<LineLink>;
+    async fn remove_link(window: Window, id: String) -> crate::Result<LineLink>;
+
+    async fn list_notes(window: Window, factory_id: Option<String>) -> crate::Result<Vec<Note>>;
+    async fn create_note(window: Window, model: NoteCreationModel) -> crate::Result<Note>;
+    async fn update_note(window: Window, note: Note) -> crate::Result<Note>;
+    async fn remove_note(window: Window, id: String) -> crate::Result<Note>;
+}
+
+#[derive(Clone)]
+pub struct FactoriesImpl {
+    projects: ProjectsImpl,
+}
+
+impl FactoriesImpl {
+    pub fn new(projects: ProjectsImpl) -> Self {
+        Self { projects }
+    }
+}
+
+#[taurpc::resolvers]
+impl FactoriesApi for FactoriesImpl {
+    async fn list_factories(self, window: Window) -> crate::Result<Vec<Factory>> {
+        self.projects.active_project(window.label()).await?.records::<Factory>().await
+    }
+
+    async fn create_factory(self, window: Window, model: FactoryCreationModel) -> crate::Result<Factory> {
+        let factory = Factory::new(model);
+        factory.validate()?;
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("create_factory", project.insert_record(factory)).await
+    }
+
+    async fn update_factory(self, window: Window, factory: Factory) -> crate::Result<Factory> {
+        factory.validate()?;
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("update_factory", project.save_record(factory)).await
+    }
+
+    async fn remove_factory(self, window: Window, id: String) -> crate::Result<Factory> {
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("remove_factory", project.remove_factory(id)).await
+    }
+
+    async fn list_lines(self, window: Window, factory_id: String) -> crate::Result<Vec<ProductionLine>> {
+        self.projects
+            .active_project(window.label())
+            .await?
+            .find_records::<ProductionLine>(doc! {"factory_id": factory_id})
+            .await
+    }
+
+    async fn create_line(self, window: Window, model: ProductionLineCreationModel) -> crate::Result<ProductionLine> {
+        let project = self.projects.active_project(window.label()).await?;
+        let line = ProductionLine::new(model);
+        line.validate()?;
+        let _ = project.get_record::<Factory>(line.factory_id.clone()).await?;
+        project.command("create_line", project.insert_record(line)).await
+    }
+
+    async fn update_line(self, window: Window, line: ProductionLine) -> crate::Result<ProductionLine> {
+        let project = self.projects.active_project(window.label()).await?;
+        line.validate()?;
+        let _ = project.get_record::<Factory>(line.factory_id.clone()).await?;
+        project.command("update_line", project.save_record(line)).await
+    }
+
+    async fn remove_line(self, window: Window, id: String) -> crate::Result<ProductionLine> {
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("remove_line", project.remove_line(id)).await
+    }
+
+    async fn list_links(self, window: Window, factory_id: String) -> crate::Result<Vec<LineLink>> {
+        self.projects
+            .active_project(window.label())
+            .await?
+            .find_records::<LineLink>(doc! {"factory_id": factory_id})
+            .await
+    }
+
+    async fn create_link(self, window: Window, model: LineLinkCreationModel) -> crate::Result<LineLink> {
+        let project = self.projects.active_project(window.label()).await?;
+        let link = LineLink::new(model);
+        link.validate()?;
+        project.check_link(&link).await?;
+        project.command("create_link", project.insert_record(link)).await
+    }
+
+    async fn update_link(self, window: Window, link: LineLink) -> crate::Result<LineLink> {
+        let project = self.projects.active_project(window.label()).await?;
+        link.validate()?;
+        project.check_link(&link).await?;
+        project.command("update_link", project.save_record(link)).await
+    }
+
+    async fn remove_link(self, window: Window, id: String) -> crate::Result<LineLink> {
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("remove_link", project.remove_record::<LineLink>(id)).await
+    }
+
+    async fn list_notes(self, window: Window, factory_id: Option<String>) -> crate::Result<Vec<Note>> {
+        let project = self.projects.active_project(window.label()).await?;
+        match factory_id {
+            Some(id) => project.find_records::<Note>(doc! {"factory_id": id}).await,
+            None => project.records::<Note>().await,
+        }
+    }
+
+    async fn create_note(self, window: Window, model: NoteCreationModel) -> crate::Result<Note> {
+        let project = self.projects.active_project(window.label()).await?;
+        let note = Note::new(model);
+        project.check_note(&note).await?;
+        project.command("create_note", project.insert_record(note)).await
+    }
+
+    async fn update_note(self, window: Window, note: Note) -> crate::Result<Note> {
+        let project = self.projects.active_project(window.label()).await?;
+        let existing = project.get_record::<Note>(note.id.clone()).await?;
+        project.check_note(&note).await?;
+        let note = Note {
+            created: existing.created,
+            updated: chrono::Utc::now(),
+            ..note
+        };
+        project.command("update_note", project.save_record(note)).await
+    }
+
+    async fn remove_note(self, window: Window, id: String) -> crate::Result<Note> {
+        let project = self.projects.active_project(window.label()).await?;
+        project.command("remove_note", project.remove_record::<Note>(id)).await
+    }
+}
@@ -0,0 +1,51 @@
+use tauri::{AppHandle, Window};
+
+use crate::utils::HistoryItem;
+
+use super::projects::ProjectsImpl;
+
+#[taurpc::procedures(path = "history", event_trigger = HistoryEventTrigger)]
+pub trait HistoryApi {
+    async fn list(window: Window) -> crate::Result<Vec<HistoryItem>>;
+    async fn undo(app_handle: AppHandle, window: Window) -> crate::Result<Option<HistoryItem>>;
+    async fn redo(app_handle: AppHandle, window: Window) -> crate::Result<Option<HistoryItem>>;
+
+    #[taurpc(event)]
+    async fn applied(item: HistoryItem);
+}
+
+#[derive(Clone)]
+pub struct HistoryImpl {
+    projects: ProjectsImpl,
+}
+
+impl HistoryImpl {
+    pub fn new(projects: ProjectsImpl) -> Self {
+        Self { projects }
+    }
+}
+
+#[taurpc::resolvers]
+impl HistoryApi for HistoryImpl {
+    async fn list(self, window: Window) -> crate::Result<Vec<HistoryItem>> {
+        self.projects.active_project(window.label()).await?.history_items().await
+    }
+
+    async fn undo(self, app_handle: AppHandle, window: Window) -> crate::Result<Option<HistoryItem>> {
+        let undone = self.projects.active_project(window.label()).await?.undo().await?;
+        if let Some(item) = undone.clone() {
+            HistoryEventTrigger::new(app_handle.clone()).applied(item)?;
+        }
+
+        Ok(undone)
+    }
+
+    async fn redo(self, app_handle: AppHandle, window: Window) -> crate::Result<Option<HistoryItem>> {
+        let redone = self.projects.active_project(window.label()).await?.redo().await?;
+        if let Some(item) = redone.clone() {
+            HistoryEventTrigger::new(app_handle.clone()).applied(item)?;
+        }
+
+        Ok(redone)
+    }
+}
@@ -1,10 +1,22 @@
 use asset_version::AssetVersionsApi;
+use data::DataApi;
+use factories::FactoriesApi;
+use history::HistoryApi;
 use projects::ProjectsApi;
+use settings::SettingsApi;
+use templates::TemplatesApi;
 use tauri::{ipc::Invoke, Wry};
 use taurpc::Router;
 
 mod asset_version;
+mod data;
+mod factories;
+mod history;
 mod projects;
+mod settings;
+mod templates;
+
+pub use projects::ProjectsImpl;
 
 #[taurpc::procedures(export_to = "../src/bindings.ts")]
 trait Api {
@@ -21,11 +33,16 @@ impl Api for ApiImpl {
     }
 }
 
-pub fn routes() -> impl Fn(Invoke) -> bool {
+pub fn routes(projects: ProjectsImpl) -> impl Fn(Invoke) -> bool {
     let mut router = Router::<Wry>::new()
         .merge(ApiImpl.into_handler())
         .merge(asset_version::AssetVersionsImpl.into_handler())
-        .merge(projects::ProjectsImpl::new().into_handler());
+        .merge(data::DataImpl::new(projects.clone()).into_handler())
+        .merge(factories::FactoriesImpl::new(projects.clone()).into_handler())
+        .merge(history::HistoryImpl::new(projects.clone()).into_handler())
+        .merge(settings::SettingsImpl.into_handler())
+        .merge(templates::TemplatesImpl::new(projects.clone()).into_handler())
+        .merge(projects.into_handler());
 
     #[cfg(debug_assertions)]
     {
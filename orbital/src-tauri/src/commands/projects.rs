@@ -1,38 +1,107 @@
+use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::sync::Arc;
-use tauri::{ipc::CommandArg, AppHandle, Manager, Runtime, Wry};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration as StdDuration};
+use tauri::{AppHandle, Manager, Window};
 use tokio::sync::Mutex;
 
-use crate::utils::{Project, ProjectConfig, ProjectsExt};
+use super::{
+    data::{load_data, preload_data},
+    settings::{forget_recent_project, push_recent_project},
+};
+use crate::utils::{
+    backups::backups, discard_storage, AppStateExt, ArchiveExt, ArchiveManifest, AssetMigrationReport, Backup, BackupsExt,
+    Project, ProjectConfig, ProjectImport, ProjectsChange, ProjectsExt, ProjectsWatcher, RecoveryInfo,
+    SnapshotReason, StorageFormat, TemplatesExt, TrashEntry, TrashExt,
+};
+
+/// Changes to an open project within this long of its own last write are assumed to be that write.
+const OWN_WRITE_WINDOW: StdDuration = StdDuration::from_secs(3);
+
+/// How often the watcher checks whether `AppState::project_path` moved.
+const WATCH_PATH_CHECK: StdDuration = StdDuration::from_secs(5);
 
 #[derive(Serialize, Deserialize, Clone, Debug, Type)]
 pub struct ProjectCreationModel {
     pub name: String,
     pub asset_version: String,
+
+    #[serde(default)]
+    pub storage: StorageFormat,
+
+    /// Id of the template to seed the project from, as listed by `templates.list`.
+    #[serde(default)]
+    pub template: Option<String>,
 }
 
-#[taurpc::procedures(path = "projects")]
+#[taurpc::procedures(path = "projects", event_trigger = ProjectsEventTrigger)]
 pub trait ProjectsApi {
     async fn list_projects(app_handle: AppHandle) -> crate::Result<Vec<ProjectConfig>>;
-    async fn create_project(app_handle: AppHandle, model: ProjectCreationModel) -> crate::Result<ProjectConfig>;
-    async fn open_project(app_handle: AppHandle, id: String) -> crate::Result<ProjectConfig>;
-    async fn close_project(app_handle: AppHandle) -> crate::Result<()>;
+    async fn create_project(app_handle: AppHandle, window: Window, model: ProjectCreationModel) -> crate::Result<ProjectConfig>;
+    async fn open_project(app_handle: AppHandle, window: Window, id: String, read_only: bool) -> crate::Result<ProjectConfig>;
+    async fn close_project(app_handle: AppHandle, window: Window) -> crate::Result<()>;
     async fn remove_project(app_handle: AppHandle, id: String) -> crate::Result<()>;
-    async fn current_project(app_handle: AppHandle) -> crate::Result<Option<ProjectConfig>>;
+    async fn current_project(window: Window) -> crate::Result<Option<ProjectConfig>>;
     async fn project_config(app_handle: AppHandle, id: String) -> crate::Result<ProjectConfig>;
+    async fn rename_project(app_handle: AppHandle, id: String, name: String) -> crate::Result<ProjectConfig>;
+    async fn export_project(app_handle: AppHandle, id: String, path: String) -> crate::Result<ArchiveManifest>;
+    async fn import_project(app_handle: AppHandle, path: String) -> crate::Result<ProjectImport>;
+    async fn migrate_asset_version(app_handle: AppHandle, project: String, target: String) -> crate::Result<AssetMigrationReport>;
+    async fn list_trash(app_handle: AppHandle) -> crate::Result<Vec<TrashEntry>>;
+    async fn restore_project(app_handle: AppHandle, entry: String) -> crate::Result<ProjectConfig>;
+    async fn purge_trash(app_handle: AppHandle, entry: Option<String>) -> crate::Result<Vec<String>>;
+    async fn list_backups(app_handle: AppHandle, id: String) -> crate::Result<Vec<Backup>>;
+    async fn restore_backup(app_handle: AppHandle, id: String, backup: String) -> crate::Result<Backup>;
+    async fn pending_recovery(window: Window) -> crate::Result<Option<RecoveryInfo>>;
+    async fn recover_project(app_handle: AppHandle, window: Window, replay: bool) -> crate::Result<u32>;
+    async fn convert_storage(app_handle: AppHandle, id: String, format: StorageFormat) -> crate::Result<ProjectConfig>;
+
+    #[taurpc(event)]
+    async fn project_created(config: ProjectConfig);
+
+    #[taurpc(event)]
+    async fn project_opened(config: ProjectConfig);
+
+    #[taurpc(event)]
+    async fn project_closed(config: ProjectConfig);
+
+    #[taurpc(event)]
+    async fn project_removed(id: String);
+
+    #[taurpc(event)]
+    async fn project_updated(config: ProjectConfig);
+
+    #[taurpc(event)]
+    async fn project_restored(config: ProjectConfig);
+
+    #[taurpc(event)]
+    async fn trash_purged(entries: Vec<String>);
+
+    #[taurpc(event)]
+    async fn recovery_available(info: RecoveryInfo);
+
+    #[taurpc(event)]
+    async fn projects_changed(change: ProjectsChange);
+
+    #[taurpc(event)]
+    async fn project_changed_on_disk(config: ProjectConfig);
+}
+
+/// A session closed by `ProjectsImpl::suspend`, to be reopened by `ProjectsImpl::resume`.
+pub struct Suspended {
+    window: String,
+    read_only: bool,
 }
 
+/// Open projects, keyed by the label of the window that opened them.
 pub struct ProjectsImpl {
-    active: Arc<Mutex<Option<String>>>,
-    project: Arc<Mutex<Option<Project>>>,
+    sessions: Arc<Mutex<HashMap<String, Project>>>,
 }
 
 impl Clone for ProjectsImpl {
     fn clone(&self) -> Self {
         Self {
-            active: self.active.clone(),
-            project: self.project.clone(),
+            sessions: self.sessions.clone(),
         }
     }
 }
@@ -40,60 +109,449 @@ impl Clone for ProjectsImpl {
 impl ProjectsImpl {
     pub fn new() -> Self {
         Self {
-            active: Arc::new(Mutex::new(None)),
-            project: Arc::new(Mutex::new(None)),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn set_active(&self, window: impl AsRef<str>, project: Project) -> () {
+        self.sessions
+            .lock()
+            .await
+            .insert(window.as_ref().to_string(), project);
+    }
+
+    /// Closes the project open in `window` (if any), returning the config of the project that was closed.
+    pub async fn clear_active(&self, window: impl AsRef<str>) -> crate::Result<Option<ProjectConfig>> {
+        let existing = self.sessions.lock().await.remove(window.as_ref());
+        if let Some(existing) = existing {
+            let config = existing.config();
+            existing.close().await?;
+            Ok(Some(config))
+        } else {
+            Ok(None)
         }
     }
 
-    pub async fn set_active(&self, project: Project) -> () {
-        let mut active = self.active.lock().await;
-        let mut project_ref = self.project.lock().await;
+    pub async fn active_project(&self, window: impl AsRef<str>) -> crate::Result<Project> {
+        self.sessions
+            .lock()
+            .await
+            .get(window.as_ref())
+            .cloned()
+            .ok_or(crate::ProjectError::no_active_project())
+    }
+
+    pub async fn active_config(&self, window: impl AsRef<str>) -> Option<ProjectConfig> {
+        self.sessions
+            .lock()
+            .await
+            .get(window.as_ref())
+            .map(|project| project.config())
+    }
 
-        active.insert(project.id());
-        project_ref.insert(project);
+    /// The session of project `id`, if some window has it open.
+    pub async fn session(&self, id: impl AsRef<str>) -> Option<Project> {
+        self.sessions
+            .lock()
+            .await
+            .values()
+            .find(|project| project.id() == id.as_ref())
+            .cloned()
     }
 
-    pub async fn clear_active(&self) -> crate::Result<()> {
-        let mut active = self.active.lock().await;
-        let mut project_ref = self.project.lock().await;
+    /// Directory of project `id`. An open project stays in the directory it was opened from, even if
+    /// `AppState::project_path` changed since.
+    pub async fn project_directory(&self, app_handle: &AppHandle, id: impl AsRef<str>) -> PathBuf {
+        let open = self
+            .sessions
+            .lock()
+            .await
+            .values()
+            .find(|project| project.id() == id.as_ref())
+            .map(|project| project.directory());
+        match open {
+            Some(directory) => directory,
+            None => app_handle.projects_directory().await.join(id.as_ref()),
+        }
+    }
+
+    /// Label of the window that has project `id` open.
+    pub async fn window_for(&self, id: impl AsRef<str>) -> Option<String> {
+        self.sessions
+            .lock()
+            .await
+            .iter()
+            .find(|(_, project)| project.id() == id.as_ref())
+            .map(|(label, _)| label.clone())
+    }
+
+    /// Snapshots every project open for writing whose newest snapshot is older than the configured interval.
+    pub async fn snapshot_open_projects(&self, app_handle: &AppHandle) -> crate::Result<()> {
+        let interval = app_handle.app_state().await.backup_interval_minutes;
+        if interval == 0 {
+            return Ok(());
+        }
+
+        let open: Vec<Project> = self.sessions.lock().await.values().cloned().collect();
+        for project in open.iter().filter(|project| !project.is_read_only()) {
+            let latest = backups(project.directory())?.first().map(|backup| backup.created);
+            if latest.is_none_or(|created| Utc::now() - created >= Duration::minutes(interval as i64)) {
+                project.snapshot(SnapshotReason::Timer).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes the project open in `window` and notifies every window that it was closed. Called for the calling
+    /// window before it opens another project, and when a window is destroyed.
+    pub async fn close_active(&self, app_handle: &AppHandle, window: impl AsRef<str>) -> crate::Result<()> {
+        if let Some(closed) = self.clear_active(window).await? {
+            ProjectsEventTrigger::new(app_handle.clone()).project_closed(closed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes project `id` if some window has it open, so its files can be read or replaced on disk. Returns what
+    /// `resume` needs to reopen it.
+    pub async fn suspend(&self, app_handle: &AppHandle, id: impl AsRef<str>) -> crate::Result<Option<Suspended>> {
+        let Some(window) = self.window_for(&id).await else {
+            return Ok(None);
+        };
+        let read_only = self.active_project(&window).await?.is_read_only();
+        self.close_active(app_handle, &window).await?;
+        Ok(Some(Suspended { window, read_only }))
+    }
+
+    /// Reopens a project closed by `suspend` in the same window and mode. Does nothing if that window has been
+    /// closed or has opened another project in the meantime.
+    pub async fn resume(&self, app_handle: &AppHandle, id: impl AsRef<str>, suspended: Option<Suspended>) -> crate::Result<()> {
+        let Some(suspended) = suspended else {
+            return Ok(());
+        };
+        if app_handle.get_webview_window(&suspended.window).is_none() || self.active_config(&suspended.window).await.is_some() {
+            return Ok(());
+        }
+
+        let config = self.clone().project_config(app_handle.clone(), id.as_ref().to_string()).await?;
+        let project = app_handle.existing_project(config, suspended.read_only).await?;
+        let reopened = project.config();
+        self.set_active(&suspended.window, project).await;
+        ProjectsEventTrigger::new(app_handle.clone()).project_opened(reopened)?;
+        Ok(())
+    }
+
+    /// Watches the projects directory for as long as the app runs, following `AppState::project_path` when it
+    /// changes. Emits `projects_changed` for every batch of changes, and `project_changed_on_disk` for each open
+    /// project that something other than its own session modified or removed.
+    pub async fn watch_projects(&self, app_handle: &AppHandle) {
+        let mut watcher: Option<ProjectsWatcher> = None;
+        loop {
+            let root = app_handle.projects_directory().await;
+            if watcher.as_ref().is_none_or(|existing| existing.root() != root) {
+                watcher = match ProjectsWatcher::new(&root) {
+                    Ok(created) => Some(created),
+                    Err(e) => {
+                        log::error!("Failed to watch projects directory {}: {e}", root.display());
+                        None
+                    }
+                };
+            }
+
+            let Some(active) = watcher.as_mut() else {
+                tokio::time::sleep(WATCH_PATH_CHECK).await;
+                continue;
+            };
+            match tokio::time::timeout(WATCH_PATH_CHECK, active.next()).await {
+                Ok(Some(change)) => {
+                    if let Err(e) = self.report_change(app_handle, change).await {
+                        log::error!("Failed to report project changes: {e}");
+                    }
+                }
+                Ok(None) => watcher = None,
+                Err(_) => (),
+            }
+        }
+    }
 
-        let _ = active.take();
-        let existing = project_ref.take();
-        if existing.is_some() {
-            existing.unwrap().close().await?;
+    async fn report_change(&self, app_handle: &AppHandle, mut change: ProjectsChange) -> crate::Result<()> {
+        let open: Vec<Project> = self.sessions.lock().await.values().cloned().collect();
+        let is_open = |config: &ProjectConfig| open.iter().any(|project| project.id() == config.id);
+        change.modified.retain(|config| {
+            !open
+                .iter()
+                .any(|project| project.id() == config.id && project.journal().wrote_within(OWN_WRITE_WINDOW))
+        });
+        if change.is_empty() {
+            return Ok(());
         }
 
+        let events = ProjectsEventTrigger::new(app_handle.clone());
+        for config in change.modified.iter().chain(change.removed.iter()).filter(|config| is_open(config)) {
+            events.project_changed_on_disk(config.clone())?;
+        }
+        events.projects_changed(change)?;
         Ok(())
     }
 }
 
 #[taurpc::resolvers]
 impl ProjectsApi for ProjectsImpl {
-    async fn list_projects(self, app_handle: AppHandle<impl Runtime>) -> crate::Result<Vec<ProjectConfig>> {
+    async fn list_projects(self, app_handle: AppHandle) -> crate::Result<Vec<ProjectConfig>> {
         app_handle.list_projects().await
     }
 
-    async fn create_project(self, app_handle: AppHandle<impl Runtime>, model: ProjectCreationModel) -> crate::Result<ProjectConfig> {
-        let new_config = ProjectConfig::new(model.name, model.asset_version);
+    async fn create_project(self, app_handle: AppHandle, window: Window, model: ProjectCreationModel) -> crate::Result<ProjectConfig> {
+        let template = match model.template {
+            Some(template) => Some(app_handle.template(template).await?),
+            None => None,
+        };
+        let mut new_config = ProjectConfig::new(model.name, model.asset_version);
+        new_config.storage = model.storage;
         let created_project = app_handle.create_project(new_config.clone()).await?;
-        self.clear_active().await?;
-        self.set_active(created_project).await;
+        if let Some(template) = template.as_ref() {
+            if let Err(e) = created_project.apply_template(template).await {
+                let directory = created_project.directory();
+                created_project.close().await?;
+                std::fs::remove_dir_all(directory)?;
+                return Err(e);
+            }
+        }
+        self.close_active(&app_handle, window.label()).await?;
+        self.set_active(window.label(), created_project).await;
+        preload_data(app_handle.clone(), new_config.asset_version.clone());
+        push_recent_project(&app_handle, new_config.id.clone()).await?;
+
+        let events = ProjectsEventTrigger::new(app_handle.clone());
+        events.project_created(new_config.clone())?;
+        events.project_opened(new_config.clone())?;
         Ok(new_config)
+    }
 
+    /// Opens a project in the calling window. Fails with `ProjectError::Locked` if another instance has it open,
+    /// in which case the UI may retry with `read_only` set.
+    async fn open_project(self, app_handle: AppHandle, window: Window, id: String, read_only: bool) -> crate::Result<ProjectConfig> {
+        if let Some(current) = self.active_config(window.label()).await {
+            if current.id == id {
+                return Ok(current);
+            }
+        }
+
+        // A project can only be open in one window at a time; bring that window forward instead.
+        if let Some(other) = self.window_for(&id).await {
+            if let Some(existing) = app_handle.get_webview_window(&other) {
+                let _ = existing.set_focus();
+            }
+            return Err(crate::ProjectError::open_in_other_window(id, other));
+        }
+
+        // The window keeps its current project until the new one is fully open.
+        let config = self.clone().project_config(app_handle.clone(), id).await?;
+        let project = app_handle.existing_project(config, read_only).await?;
+        let opened = project.config();
+        let recovery = match project.pending_recovery() {
+            Ok(recovery) => recovery,
+            Err(e) => {
+                project.close().await?;
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.close_active(&app_handle, window.label()).await {
+            project.close().await?;
+            return Err(e);
+        }
+        self.set_active(window.label(), project).await;
+        preload_data(app_handle.clone(), opened.asset_version.clone());
+        push_recent_project(&app_handle, opened.id.clone()).await?;
+
+        let events = ProjectsEventTrigger::new(app_handle.clone());
+        events.project_opened(opened.clone())?;
+        if let Some(info) = recovery {
+            events.recovery_available(info)?;
+        }
+        Ok(opened)
     }
-    async fn open_project(self, app_handle: AppHandle<impl Runtime>, id: String) -> crate::Result<ProjectConfig> {
 
+    async fn close_project(self, app_handle: AppHandle, window: Window) -> crate::Result<()> {
+        self.close_active(&app_handle, window.label()).await
     }
-    async fn close_project(self, app_handle: AppHandle<impl Runtime>) -> crate::Result<()> {
 
+    async fn remove_project(self, app_handle: AppHandle, id: String) -> crate::Result<()> {
+        if let Some(window) = self.window_for(&id).await {
+            self.close_active(&app_handle, window).await?;
+        }
+
+        app_handle.remove_project(id.clone()).await?;
+        forget_recent_project(&app_handle, id.clone()).await?;
+        ProjectsEventTrigger::new(app_handle.clone()).project_removed(id)?;
+        Ok(())
+    }
+
+    async fn current_project(self, window: Window) -> crate::Result<Option<ProjectConfig>> {
+        Ok(self.active_config(window.label()).await)
+    }
+
+    async fn project_config(self, app_handle: AppHandle, id: String) -> crate::Result<ProjectConfig> {
+        app_handle
+            .list_projects()
+            .await?
+            .into_iter()
+            .find(|config| config.id == id)
+            .ok_or(crate::ProjectError::not_exists(id))
+    }
+
+    async fn rename_project(self, app_handle: AppHandle, id: String, name: String) -> crate::Result<ProjectConfig> {
+        let renamed = {
+            let mut sessions = self.sessions.lock().await;
+            match sessions.values_mut().find(|project| project.id() == id) {
+                Some(project) => {
+                    let name = name.trim().to_string();
+                    if name.is_empty() {
+                        return Err(crate::ProjectError::invalid_name(name));
+                    }
+
+                    let mut config = project.config();
+                    config.name = name;
+                    project.update_config(config).await?
+                }
+                None => app_handle.rename_project(id, name).await?,
+            }
+        };
+
+        ProjectsEventTrigger::new(app_handle.clone()).project_updated(renamed.clone())?;
+        Ok(renamed)
     }
-    async fn remove_project(self, app_handle: AppHandle<impl Runtime>, id: String) -> crate::Result<()> {
 
+    /// Exports a project. A project open in some window is closed for the export, so its database is flushed, and
+    /// reopened there afterwards.
+    async fn export_project(self, app_handle: AppHandle, id: String, path: String) -> crate::Result<ArchiveManifest> {
+        let suspended = self.suspend(&app_handle, &id).await?;
+        let exported = app_handle.export_project(id.clone(), PathBuf::from(path)).await;
+        self.resume(&app_handle, id, suspended).await?;
+        exported
     }
-    async fn current_project(self, app_handle: AppHandle<impl Runtime>) -> crate::Result<Option<ProjectConfig>> {
 
+    async fn import_project(self, app_handle: AppHandle, path: String) -> crate::Result<ProjectImport> {
+        let imported = app_handle.import_project(PathBuf::from(path)).await?;
+        ProjectsEventTrigger::new(app_handle.clone()).project_created(imported.config.clone())?;
+        Ok(imported)
     }
-    async fn project_config(self, app_handle: AppHandle<impl Runtime>, id: String) -> crate::Result<ProjectConfig> {
 
+    async fn migrate_asset_version(self, app_handle: AppHandle, project: String, target: String) -> crate::Result<AssetMigrationReport> {
+        let window = self.window_for(&project).await;
+        let mut opened = if let Some(window) = window.as_ref() {
+            self.active_project(window).await?
+        } else {
+            let config = self.clone().project_config(app_handle.clone(), project).await?;
+            app_handle.existing_project(config, false).await?
+        };
+        opened.ensure_writable()?;
+
+        let mut config = opened.config();
+        let from = load_data(app_handle.clone(), config.asset_version.clone()).await?;
+        let to = load_data(app_handle.clone(), target.clone()).await?;
+
+        let result = opened
+            .command("migrate_asset_version", opened.migrate_asset_version(&from, &to))
+            .await;
+        let report = match result {
+            Ok(report) => {
+                config.asset_version = target;
+                opened.update_config(config.clone()).await?;
+                report
+            }
+            Err(e) => {
+                if window.is_none() {
+                    opened.close().await?;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(window) = window {
+            // The window may have closed the project or switched to another one while the migration ran.
+            let mut sessions = self.sessions.lock().await;
+            if let Some(session) = sessions.get_mut(&window).filter(|session| session.id() == opened.id()) {
+                *session = opened;
+            }
+        } else {
+            opened.close().await?;
+        }
+
+        ProjectsEventTrigger::new(app_handle.clone()).project_updated(config)?;
+        Ok(report)
+    }
+
+    async fn list_trash(self, app_handle: AppHandle) -> crate::Result<Vec<TrashEntry>> {
+        app_handle.list_trash().await
+    }
+
+    async fn restore_project(self, app_handle: AppHandle, entry: String) -> crate::Result<ProjectConfig> {
+        let restored = app_handle.restore_project(entry).await?;
+        ProjectsEventTrigger::new(app_handle.clone()).project_restored(restored.clone())?;
+        Ok(restored)
+    }
+
+    async fn purge_trash(self, app_handle: AppHandle, entry: Option<String>) -> crate::Result<Vec<String>> {
+        let purged = app_handle.purge_trash(entry).await?;
+        ProjectsEventTrigger::new(app_handle.clone()).trash_purged(purged.clone())?;
+        Ok(purged)
+    }
+
+    async fn list_backups(self, app_handle: AppHandle, id: String) -> crate::Result<Vec<Backup>> {
+        let directory = self.project_directory(&app_handle, &id).await;
+        app_handle.list_backups(id, directory).await
+    }
+
+    /// Restores a snapshot. A project open in some window is closed for the restore and reopened there afterwards.
+    async fn restore_backup(self, app_handle: AppHandle, id: String, backup: String) -> crate::Result<Backup> {
+        let directory = self.project_directory(&app_handle, &id).await;
+        let suspended = self.suspend(&app_handle, &id).await?;
+        let restored = app_handle.restore_backup(id.clone(), directory, backup).await;
+        self.resume(&app_handle, id, suspended).await?;
+        restored
+    }
+
+    async fn pending_recovery(self, window: Window) -> crate::Result<Option<RecoveryInfo>> {
+        self.active_project(window.label()).await?.pending_recovery()
+    }
+
+    /// Replays (or, with `replay = false`, discards) the journal left by an unclean shutdown of the active project.
+    async fn recover_project(self, app_handle: AppHandle, window: Window, replay: bool) -> crate::Result<u32> {
+        let project = self.active_project(window.label()).await?;
+        if !replay {
+            project.ensure_writable()?;
+            project.journal().discard_pending()?;
+            return Ok(0);
+        }
+
+        let applied = project.replay_journal().await?;
+        ProjectsEventTrigger::new(app_handle.clone()).project_updated(project.config())?;
+        Ok(applied)
+    }
+
+    /// Moves a project's records into another storage format. A project open in some window is closed for the
+    /// conversion and reopened in that window afterwards.
+    async fn convert_storage(self, app_handle: AppHandle, id: String, format: StorageFormat) -> crate::Result<ProjectConfig> {
+        let suspended = self.suspend(&app_handle, &id).await?;
+        let converted = async {
+            let config = self.clone().project_config(app_handle.clone(), id.clone()).await?;
+            let from = config.storage;
+            let mut project = app_handle.existing_project(config, false).await?;
+            let directory = project.directory();
+            let converted = project.convert_storage(format).await;
+            project.close().await?;
+            converted?;
+            discard_storage(&directory, from, format)
+        }
+        .await;
+        let resumed = self.resume(&app_handle, id.clone(), suspended).await;
+        converted?;
+        resumed?;
+
+        let config = self.clone().project_config(app_handle.clone(), id).await?;
+        ProjectsEventTrigger::new(app_handle.clone()).project_updated(config.clone())?;
+        Ok(config)
     }
 }
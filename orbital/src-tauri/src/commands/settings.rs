@@ -0,0 +1,63 @@
+use tauri::AppHandle;
+
+use crate::utils::{AppState, AppStateExt, AppStatePatcher, StatePatch};
+
+#[taurpc::procedures(path = "settings", event_trigger = SettingsEventTrigger)]
+pub trait SettingsApi {
+    async fn get(app_handle: AppHandle) -> crate::Result<AppState>;
+    async fn update(app_handle: AppHandle, patch: AppStatePatcher) -> crate::Result<AppState>;
+
+    #[taurpc(event)]
+    async fn changed(settings: AppState);
+}
+
+/// Records `id` as the most recently opened project and tells every window about the new list.
+pub async fn push_recent_project(app_handle: &AppHandle, id: String) -> crate::Result<()> {
+    let state = app_handle.push_recent_project(id).await?;
+    SettingsEventTrigger::new(app_handle.clone()).changed(state)?;
+    Ok(())
+}
+
+/// Drops a removed project from the recent projects list.
+pub async fn forget_recent_project(app_handle: &AppHandle, id: String) -> crate::Result<()> {
+    let mut recent = app_handle.app_state().await.recent_projects;
+    if !recent.contains(&id) {
+        return Ok(());
+    }
+
+    recent.retain(|existing| existing != &id);
+    let mut patch = StatePatch::default();
+    patch.recent_projects(recent);
+    let state = app_handle.patch_app_state(patch).await?;
+    SettingsEventTrigger::new(app_handle.clone()).changed(state)?;
+    Ok(())
+}
+
+/// Clears the default asset version if it is `name`, i.e. after that version was uninstalled.
+pub async fn forget_default_asset_version(app_handle: &AppHandle, name: String) -> crate::Result<()> {
+    if app_handle.app_state().await.default_asset_version != Some(name) {
+        return Ok(());
+    }
+
+    let mut patch = StatePatch::default();
+    patch.default_asset_version(None::<String>);
+    let state = app_handle.patch_app_state(patch).await?;
+    SettingsEventTrigger::new(app_handle.clone()).changed(state)?;
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct SettingsImpl;
+
+#[taurpc::resolvers]
+impl SettingsApi for SettingsImpl {
+    async fn get(self, app_handle: AppHandle) -> crate::Result<AppState> {
+        Ok(app_handle.app_state().await)
+    }
+
+    async fn update(self, app_handle: AppHandle, patch: AppStatePatcher) -> crate::Result<AppState> {
+        let state = app_handle.apply_app_state_patch(patch).await?;
+        SettingsEventTrigger::new(app_handle.clone()).changed(state.clone())?;
+        Ok(state)
+    }
+}
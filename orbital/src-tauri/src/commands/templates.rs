@@ -0,0 +1,66 @@
+use tauri::AppHandle;
+
+use crate::utils::{ProjectConfig, ProjectsExt, TemplateInfo, TemplatesExt};
+
+use super::projects::ProjectsImpl;
+
+#[taurpc::procedures(path = "templates", event_trigger = TemplatesEventTrigger)]
+pub trait TemplatesApi {
+    async fn list(app_handle: AppHandle) -> crate::Result<Vec<TemplateInfo>>;
+    async fn save(app_handle: AppHandle, project: String, name: String, description: String) -> crate::Result<TemplateInfo>;
+    async fn remove(app_handle: AppHandle, id: String) -> crate::Result<()>;
+
+    #[taurpc(event)]
+    async fn saved(template: TemplateInfo);
+
+    #[taurpc(event)]
+    async fn removed(id: String);
+}
+
+#[derive(Clone)]
+pub struct TemplatesImpl {
+    projects: ProjectsImpl,
+}
+
+impl TemplatesImpl {
+    pub fn new(projects: ProjectsImpl) -> Self {
+        Self { projects }
+    }
+}
+
+#[taurpc::resolvers]
+impl TemplatesApi for TemplatesImpl {
+    async fn list(self, app_handle: AppHandle) -> crate::Result<Vec<TemplateInfo>> {
+        app_handle.list_templates().await
+    }
+
+    /// Saves the records of a project as a user template. A project that no window has open is opened read-only
+    /// for the copy.
+    async fn save(self, app_handle: AppHandle, project: String, name: String, description: String) -> crate::Result<TemplateInfo> {
+        let template = match self.projects.session(&project).await {
+            Some(open) => open.to_template(name, description).await?,
+            None => {
+                let config: ProjectConfig = app_handle
+                    .list_projects()
+                    .await?
+                    .into_iter()
+                    .find(|config| config.id == project)
+                    .ok_or(crate::ProjectError::not_exists(project))?;
+                let opened = app_handle.existing_project(config, true).await?;
+                let template = opened.to_template(name, description).await;
+                opened.close().await?;
+                template?
+            }
+        };
+
+        let saved = app_handle.save_template(template).await?;
+        TemplatesEventTrigger::new(app_handle.clone()).saved(saved.clone())?;
+        Ok(saved)
+    }
+
+    async fn remove(self, app_handle: AppHandle, id: String) -> crate::Result<()> {
+        app_handle.remove_template(id.clone()).await?;
+        TemplatesEventTrigger::new(app_handle.clone()).removed(id)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,150 @@
+use std::{collections::BTreeMap, path::Path};
+
+use orbital_common::{error_params, ErrorCode};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
 #[derive(thiserror::Error, Serialize, Deserialize, Type, Clone, Debug)]
 #[serde(tag = "kind", rename_all = "snake_case")]
-pub enum AssetVersionError {}
+pub enum AssetVersionError {
+    #[error("Invalid asset version name: {name:?}")]
+    InvalidName {
+        name: String
+    },
+
+    #[error("Invalid asset pack: {reason}")]
+    InvalidPack {
+        reason: String
+    },
+
+    #[error("Asset version already installed: {name}")]
+    AlreadyInstalled {
+        name: String
+    },
+
+    #[error("Asset version not installed: {name}")]
+    NotInstalled {
+        name: String
+    },
+
+    #[error("Asset version {name} is bundled with Orbital and cannot be uninstalled")]
+    Bundled {
+        name: String
+    },
+
+    #[error("Asset version {name} is used by: {}", projects.join(", "))]
+    InUse {
+        name: String,
+        projects: Vec<String>
+    }
+}
+
+impl AssetVersionError {
+    fn wrap(self) -> crate::Error {
+        CommandError::AssetVersion { error: self }.into()
+    }
+
+    pub fn invalid_name(name: impl AsRef<str>) -> crate::Error {
+        Self::InvalidName { name: name.as_ref().to_string() }.wrap()
+    }
+
+    pub fn invalid_pack(reason: impl AsRef<str>) -> crate::Error {
+        Self::InvalidPack { reason: reason.as_ref().to_string() }.wrap()
+    }
+
+    pub fn already_installed(name: impl AsRef<str>) -> crate::Error {
+        Self::AlreadyInstalled { name: name.as_ref().to_string() }.wrap()
+    }
+
+    pub fn not_installed(name: impl AsRef<str>) -> crate::Error {
+        Self::NotInstalled { name: name.as_ref().to_string() }.wrap()
+    }
+
+    pub fn bundled(name: impl AsRef<str>) -> crate::Error {
+        Self::Bundled { name: name.as_ref().to_string() }.wrap()
+    }
+
+    pub fn in_use(name: impl AsRef<str>, projects: Vec<String>) -> crate::Error {
+        Self::InUse { name: name.as_ref().to_string(), projects }.wrap()
+    }
+}
+
+impl ErrorCode for AssetVersionError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidName { .. } => "asset_versions.invalid_name",
+            Self::InvalidPack { .. } => "asset_versions.invalid_pack",
+            Self::AlreadyInstalled { .. } => "asset_versions.already_installed",
+            Self::NotInstalled { .. } => "asset_versions.not_installed",
+            Self::Bundled { .. } => "asset_versions.bundled",
+            Self::InUse { .. } => "asset_versions.in_use"
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::InvalidName { name } | Self::AlreadyInstalled { name } | Self::NotInstalled { name } | Self::Bundled { name } => error_params! { "name" => name },
+            Self::InvalidPack { reason } => error_params! { "reason" => reason },
+            Self::InUse { name, projects } => error_params! { "name" => name, "projects" => projects.join(", ") }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Serialize, Deserialize, Type, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SettingsError {
+    #[error("Invalid project folder {folder}: {reason}")]
+    InvalidProjectPath {
+        folder: String,
+        reason: String
+    },
+
+    #[error("Invalid locale: {locale:?}")]
+    InvalidLocale {
+        locale: String
+    },
+
+    #[error("Unknown asset version: {name}")]
+    UnknownAssetVersion {
+        name: String
+    }
+}
+
+impl SettingsError {
+    fn wrap(self) -> crate::Error {
+        CommandError::Settings { error: self }.into()
+    }
+
+    pub fn invalid_project_path(folder: impl AsRef<Path>, reason: impl AsRef<str>) -> crate::Error {
+        Self::InvalidProjectPath { folder: folder.as_ref().to_str().unwrap_or("BAD_PATH").to_string(), reason: reason.as_ref().to_string() }.wrap()
+    }
+
+    pub fn invalid_locale(locale: impl AsRef<str>) -> crate::Error {
+        Self::InvalidLocale { locale: locale.as_ref().to_string() }.wrap()
+    }
+
+    pub fn unknown_asset_version(name: impl AsRef<str>) -> crate::Error {
+        Self::UnknownAssetVersion { name: name.as_ref().to_string() }.wrap()
+    }
+}
+
+impl ErrorCode for SettingsError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidProjectPath { .. } => "settings.invalid_project_path",
+            Self::InvalidLocale { .. } => "settings.invalid_locale",
+            Self::UnknownAssetVersion { .. } => "settings.unknown_asset_version"
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::InvalidProjectPath { folder, reason } => error_params! { "folder" => folder, "reason" => reason },
+            Self::InvalidLocale { locale } => error_params! { "locale" => locale },
+            Self::UnknownAssetVersion { name } => error_params! { "name" => name }
+        }
+    }
+}
 
 #[derive(thiserror::Error, Serialize, Deserialize, Type, Clone, Debug)]
 #[serde(tag = "path")]
@@ -13,5 +154,28 @@ pub enum CommandError {
     AssetVersion {
         #[serde(flatten)]
         error: AssetVersionError
+    },
+
+    #[error(transparent)]
+    #[serde(rename = "api.settings")]
+    Settings {
+        #[serde(flatten)]
+        error: SettingsError
+    }
+}
+
+impl ErrorCode for CommandError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::AssetVersion { error } => error.code(),
+            Self::Settings { error } => error.code()
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::AssetVersion { error } => error.params(),
+            Self::Settings { error } => error.params()
+        }
     }
 }
@@ -1,5 +1,7 @@
-use std::{fmt::Display, io, path::Path, sync::Arc};
+use std::{collections::BTreeMap, fmt::Display, io, path::Path, sync::Arc};
 
+use chrono::{DateTime, Utc};
+use orbital_common::{error_params, ErrorCode, ErrorInfo};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 mod commands;
@@ -67,45 +69,227 @@ pub enum ProjectError {
     #[error("Project does not exist: {id}")]
     ProjectDoesntExist {
         id: String
+    },
+
+    #[error("Invalid project name: {name:?}")]
+    InvalidName {
+        name: String
+    },
+
+    #[error("No project is currently open")]
+    NoActiveProject,
+
+    #[error("Backup {backup} of project {id} does not exist")]
+    BackupDoesntExist {
+        id: String,
+        backup: String
+    },
+
+    #[error("Trash entry does not exist: {entry}")]
+    TrashEntryDoesntExist {
+        entry: String
+    },
+
+    #[error("Template does not exist: {template}")]
+    TemplateDoesntExist {
+        template: String
+    },
+
+    #[error("Project {id} is locked by process {pid} on {host}")]
+    Locked {
+        id: String,
+        pid: u32,
+        host: String,
+        acquired: DateTime<Utc>
+    },
+
+    #[error("Project {id} is open read-only")]
+    ReadOnly {
+        id: String
+    },
+
+    #[error("Project {id} is already open in window {window}")]
+    OpenInOtherWindow {
+        id: String,
+        window: String
+    },
+
+    #[error("Record does not exist: {collection}/{id}")]
+    RecordDoesntExist {
+        collection: String,
+        id: String
+    },
+
+    #[error("Invalid {collection} record: {reason}")]
+    InvalidRecord {
+        collection: String,
+        reason: String
+    },
+
+    #[error("Invalid project archive: {reason}")]
+    InvalidArchive {
+        reason: String
+    },
+
+    #[error("Project {id} uses schema version {version}, but this version of Orbital supports up to {supported}")]
+    UnsupportedSchema {
+        id: String,
+        version: u32,
+        supported: u32
+    }
+}
+
+impl ErrorCode for OperationError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidPathError { .. } => "operation.invalid_path",
+            Self::Filesystem { .. } => "operation.filesystem",
+            Self::Serialization { .. } => "operation.serialization",
+            Self::Deserialization { .. } => "operation.deserialization"
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::InvalidPathError { path, invalid } => error_params! { "path" => path, "invalid" => invalid.to_string().trim_matches('"') },
+            Self::Filesystem { reason, .. } | Self::Serialization { reason } | Self::Deserialization { reason } => error_params! { "reason" => reason }
+        }
     }
 }
 
 impl ProjectError {
     pub fn exists(id: impl AsRef<str>) -> Error {
-        Error::Project { error: Self::ProjectExists { id: id.as_ref().to_string() } }
+        Error::from(Self::ProjectExists { id: id.as_ref().to_string() })
     }
 
     pub fn not_exists(id: impl AsRef<str>) -> Error {
-        Error::Project { error: Self::ProjectDoesntExist { id: id.as_ref().to_string() } }
+        Error::from(Self::ProjectDoesntExist { id: id.as_ref().to_string() })
+    }
+
+    pub fn invalid_name(name: impl AsRef<str>) -> Error {
+        Error::from(Self::InvalidName { name: name.as_ref().to_string() })
+    }
+
+    pub fn no_active_project() -> Error {
+        Error::from(Self::NoActiveProject)
+    }
+
+    pub fn backup_not_exists(id: impl AsRef<str>, backup: impl AsRef<str>) -> Error {
+        Error::from(Self::BackupDoesntExist { id: id.as_ref().to_string(), backup: backup.as_ref().to_string() })
+    }
+
+    pub fn trash_entry_not_exists(entry: impl AsRef<str>) -> Error {
+        Error::from(Self::TrashEntryDoesntExist { entry: entry.as_ref().to_string() })
+    }
+
+    pub fn template_not_exists(template: impl AsRef<str>) -> Error {
+        Error::from(Self::TemplateDoesntExist { template: template.as_ref().to_string() })
+    }
+
+    pub fn locked(id: impl AsRef<str>, lock: &crate::utils::ProjectLock) -> Error {
+        Error::from(Self::Locked { id: id.as_ref().to_string(), pid: lock.pid, host: lock.host.clone(), acquired: lock.acquired })
+    }
+
+    pub fn read_only(id: impl AsRef<str>) -> Error {
+        Error::from(Self::ReadOnly { id: id.as_ref().to_string() })
+    }
+
+    pub fn open_in_other_window(id: impl AsRef<str>, window: impl AsRef<str>) -> Error {
+        Error::from(Self::OpenInOtherWindow { id: id.as_ref().to_string(), window: window.as_ref().to_string() })
+    }
+
+    pub fn record_not_exists(collection: impl AsRef<str>, id: impl AsRef<str>) -> Error {
+        Error::from(Self::RecordDoesntExist { collection: collection.as_ref().to_string(), id: id.as_ref().to_string() })
+    }
+
+    pub fn invalid_archive(reason: impl AsRef<str>) -> Error {
+        Error::from(Self::InvalidArchive { reason: reason.as_ref().to_string() })
+    }
+
+    pub fn unsupported_schema(id: impl AsRef<str>, version: u32) -> Error {
+        Error::from(Self::UnsupportedSchema { id: id.as_ref().to_string(), version, supported: crate::utils::projects::SCHEMA_VERSION })
+    }
+
+    pub fn invalid_record(collection: impl AsRef<str>, reason: impl AsRef<str>) -> Error {
+        Error::from(Self::InvalidRecord { collection: collection.as_ref().to_string(), reason: reason.as_ref().to_string() })
     }
 }
 
+impl ErrorCode for ProjectError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::ProjectExists { .. } => "project.exists",
+            Self::ProjectDoesntExist { .. } => "project.not_found",
+            Self::InvalidName { .. } => "project.invalid_name",
+            Self::NoActiveProject => "project.no_active_project",
+            Self::BackupDoesntExist { .. } => "project.backup_not_found",
+            Self::TrashEntryDoesntExist { .. } => "project.trash_entry_not_found",
+            Self::TemplateDoesntExist { .. } => "project.template_not_found",
+            Self::Locked { .. } => "project.locked",
+            Self::ReadOnly { .. } => "project.read_only",
+            Self::OpenInOtherWindow { .. } => "project.open_in_other_window",
+            Self::RecordDoesntExist { .. } => "project.record_not_found",
+            Self::InvalidRecord { .. } => "project.invalid_record",
+            Self::InvalidArchive { .. } => "project.invalid_archive",
+            Self::UnsupportedSchema { .. } => "project.unsupported_schema"
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::ProjectExists { id } | Self::ProjectDoesntExist { id } | Self::ReadOnly { id } => error_params! { "id" => id },
+            Self::BackupDoesntExist { id, backup } => error_params! { "id" => id, "backup" => backup },
+            Self::TrashEntryDoesntExist { entry } => error_params! { "entry" => entry },
+            Self::TemplateDoesntExist { template } => error_params! { "template" => template },
+            Self::Locked { id, pid, host, acquired } => error_params! { "id" => id, "pid" => pid, "host" => host, "acquired" => acquired.to_rfc3339() },
+            Self::InvalidName { name } => error_params! { "name" => name },
+            Self::NoActiveProject => BTreeMap::new(),
+            Self::OpenInOtherWindow { id, window } => error_params! { "id" => id, "window" => window },
+            Self::RecordDoesntExist { collection, id } => error_params! { "collection" => collection, "id" => id },
+            Self::InvalidRecord { collection, reason } => error_params! { "collection" => collection, "reason" => reason },
+            Self::InvalidArchive { reason } => error_params! { "reason" => reason },
+            Self::UnsupportedSchema { id, version, supported } => error_params! { "id" => id, "version" => version, "supported" => supported }
+        }
+    }
+}
+
+/// Top-level error returned by every command. Each variant carries an [`ErrorInfo`] (stable code,
+/// localization key and parameters) computed from the wrapped error when it is constructed.
 #[derive(thiserror::Error, Serialize, Deserialize, Type, Clone, Debug)]
 #[serde(tag = "source", rename_all = "snake_case")]
 pub enum Error {
     #[error("Encountered an error in backend utilities: {error}")]
     CommonInternals {
         #[serde(flatten)]
-        #[from]
+        info: ErrorInfo,
+
+        #[serde(flatten)]
         error: orbital_common::CommonError,
     },
 
     #[error("Encountered a command error: {error}")]
     Command {
         #[serde(flatten)]
-        #[from]
+        info: ErrorInfo,
+
+        #[serde(flatten)]
         error: commands::CommandError,
     },
 
     #[error("Encountered a common operation error: {error}")]
     Operation {
         #[serde(flatten)]
-        #[from]
+        info: ErrorInfo,
+
+        #[serde(flatten)]
         error: OperationError
     },
 
     #[error("Internal error in a Tauri API: {reason}")]
     Tauri {
+        #[serde(flatten)]
+        info: ErrorInfo,
+
         reason: String,
 
         #[serde(skip)]
@@ -115,38 +299,299 @@ pub enum Error {
     #[error("Encountered an error in the Persistence API: {error:?}")]
     Persistence {
         #[serde(flatten)]
-        #[from]
+        info: ErrorInfo,
+
+        #[serde(flatten)]
         error: tauri_plugin_persistence::Error
     },
 
     #[error("Project management error: {error:?}")]
     Project {
         #[serde(flatten)]
-        #[from]
+        info: ErrorInfo,
+
+        #[serde(flatten)]
         error: ProjectError
     }
 }
 
+impl From<orbital_common::CommonError> for Error {
+    fn from(value: orbital_common::CommonError) -> Self {
+        Self::CommonInternals { info: value.info(), error: value }
+    }
+}
+
+impl From<commands::CommandError> for Error {
+    fn from(value: commands::CommandError) -> Self {
+        Self::Command { info: value.info(), error: value }
+    }
+}
+
+impl From<OperationError> for Error {
+    fn from(value: OperationError) -> Self {
+        Self::Operation { info: value.info(), error: value }
+    }
+}
+
+/// Stable code for an error from the Persistence API, which can't implement `ErrorCode` itself.
+fn persistence_code(error: &tauri_plugin_persistence::Error) -> &'static str {
+    use tauri_plugin_persistence::Error as E;
+    match error {
+        E::Unknown { .. } => "persistence.unknown",
+        E::OpenContext { .. } => "persistence.open_context",
+        E::OpenDatabase { .. } => "persistence.open_database",
+        E::OpenFileHandle { .. } => "persistence.open_file_handle",
+        E::UnknownContext { .. } => "persistence.unknown_context",
+        E::UnknownDatabase { .. } => "persistence.unknown_database",
+        E::UnknownFileHandle { .. } => "persistence.unknown_file_handle",
+        E::UnknownTransaction { .. } => "persistence.unknown_transaction",
+        E::InvalidPath { .. } => "persistence.invalid_path",
+        E::NoAbsolutePaths { .. } => "persistence.no_absolute_paths",
+        E::PathEscapesContext { .. } => "persistence.path_escapes_context",
+        E::DatabaseError { .. } => "persistence.database",
+        E::SerializationError { .. } => "persistence.serialization",
+        E::DeserializationError { .. } => "persistence.deserialization",
+        E::IOError { .. } => "persistence.io",
+        E::StringEncodingError { .. } => "persistence.string_encoding",
+        E::FilesystemError { .. } => "persistence.filesystem"
+    }
+}
+
+impl From<tauri_plugin_persistence::Error> for Error {
+    fn from(value: tauri_plugin_persistence::Error) -> Self {
+        let info = ErrorInfo::new(persistence_code(&value), error_params! { "reason" => value });
+
+        Self::Persistence { info, error: value }
+    }
+}
+
+impl From<ProjectError> for Error {
+    fn from(value: ProjectError) -> Self {
+        Self::Project { info: value.info(), error: value }
+    }
+}
+
 impl From<tauri::Error> for Error {
     fn from(value: tauri::Error) -> Self {
-        Self::Tauri { reason: format!("{value:?}"), error: Some(Arc::new(value)) }
+        let reason = format!("{value:?}");
+        let info = ErrorInfo::new("tauri.internal", error_params! { "reason" => reason });
+
+        Self::Tauri { info, reason, error: Some(Arc::new(value)) }
     }
 }
 
 impl From<io::Error> for Error {
     fn from(value: io::Error) -> Self {
-        Self::Operation { error: OperationError::Filesystem { reason: value.to_string(), error: Some(Arc::new(value)) } }
+        OperationError::Filesystem { reason: value.to_string(), error: Some(Arc::new(value)) }.into()
+    }
+}
+
+impl From<notify_debouncer_mini::notify::Error> for Error {
+    fn from(value: notify_debouncer_mini::notify::Error) -> Self {
+        OperationError::Filesystem { reason: value.to_string(), error: None }.into()
     }
 }
 
 impl Error {
     pub fn serialization(error: impl serde::ser::Error) -> Self {
-        Self::Operation { error: OperationError::Serialization { reason: error.to_string() } }
+        OperationError::Serialization { reason: error.to_string() }.into()
     }
 
     pub fn deserialization(error: impl serde::de::Error) -> Self {
-        Self::Operation { error: OperationError::Deserialization { reason: error.to_string() } }
+        OperationError::Deserialization { reason: error.to_string() }.into()
+    }
+
+    /// Stable code, localization key and parameters for this error.
+    pub fn info(&self) -> &ErrorInfo {
+        match self {
+            Self::CommonInternals { info, .. }
+            | Self::Command { info, .. }
+            | Self::Operation { info, .. }
+            | Self::Tauri { info, .. }
+            | Self::Persistence { info, .. }
+            | Self::Project { info, .. } => info
+        }
     }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One value of every error variant the Tauri crate defines, every Persistence API error and a Tauri error.
+    fn every_error() -> Vec<Error> {
+        use tauri_plugin_persistence::Error as Persistence;
+        vec![
+            OperationError::invalid_path("/tmp", InvalidPathType::NotExists),
+            io::Error::other("denied").into(),
+            Error::serialization(serde_json::Error::io(io::Error::other("closed"))),
+            Error::deserialization(serde_json::Error::io(io::Error::other("closed"))),
+            ProjectError::exists("a"),
+            ProjectError::not_exists("a"),
+            ProjectError::invalid_name(""),
+            ProjectError::no_active_project(),
+            ProjectError::backup_not_exists("a", "b"),
+            ProjectError::trash_entry_not_exists("a"),
+            ProjectError::TemplateDoesntExist { template: "t".to_string() }.into(),
+            ProjectError::Locked { id: "a".to_string(), pid: 1, host: "h".to_string(), acquired: Utc::now() }.into(),
+            ProjectError::ReadOnly { id: "a".to_string() }.into(),
+            ProjectError::OpenInOtherWindow { id: "a".to_string(), window: "main".to_string() }.into(),
+            ProjectError::RecordDoesntExist { collection: "factories".to_string(), id: "f".to_string() }.into(),
+            ProjectError::InvalidRecord { collection: "factories".to_string(), reason: "r".to_string() }.into(),
+            ProjectError::InvalidArchive { reason: "r".to_string() }.into(),
+            ProjectError::UnsupportedSchema { id: "a".to_string(), version: 2, supported: 1 }.into(),
+            AssetVersionError::invalid_name(""),
+            AssetVersionError::invalid_pack("r"),
+            AssetVersionError::already_installed("1.0"),
+            AssetVersionError::not_installed("1.0"),
+            AssetVersionError::bundled("1.0"),
+            AssetVersionError::in_use("1.0", vec!["a".to_string()]),
+            SettingsError::invalid_project_path("projects", "Path must be absolute"),
+            SettingsError::invalid_locale("x"),
+            SettingsError::unknown_asset_version("1.0"),
+            Persistence::Unknown { reason: "r".to_string() }.into(),
+            Persistence::open_context("a", "/p", "r").into(),
+            Persistence::open_database("project", "a", "/p", "r").into(),
+            Persistence::open_file_handle("project.json", "a", "r").into(),
+            Persistence::unknown_context("a").into(),
+            Persistence::unknown_database("project").into(),
+            Persistence::unknown_file_handle("1").into(),
+            Persistence::unknown_transaction("1").into(),
+            Persistence::invalid_path("/p").into(),
+            Persistence::no_absolute_path("/p").into(),
+            Persistence::path_escapes_context("../p").into(),
+            Persistence::DatabaseError { reason: "r".to_string() }.into(),
+            Persistence::SerializationError { reason: "r".to_string() }.into(),
+            Persistence::DeserializationError { reason: "r".to_string() }.into(),
+            Persistence::IOError { reason: "r".to_string() }.into(),
+            Persistence::string_encoding(3).into(),
+            Persistence::filesystem("copy", "r").into(),
+            tauri::Error::WindowNotFound.into(),
+            orbital_common::CommonError::from(orbital_common::DocsError::unknown_locale("xx")).into(),
+        ]
+    }
+
+    /// Category and position of an error's variant. The matches are exhaustive, so adding a variant doesn't compile
+    /// until it is listed here, and `every_variant_is_sampled` fails until `every_error` samples it.
+    fn variant(error: &Error) -> (&'static str, usize) {
+        use tauri_plugin_persistence::Error as Persistence;
+        match error {
+            Error::Operation { error, .. } => (
+                "operation",
+                match error {
+                    OperationError::InvalidPathError { .. } => 0,
+                    OperationError::Filesystem { .. } => 1,
+                    OperationError::Serialization { .. } => 2,
+                    OperationError::Deserialization { .. } => 3
+                }
+            ),
+            Error::Project { error, .. } => (
+                "project",
+                match error {
+                    ProjectError::ProjectExists { .. } => 0,
+                    ProjectError::ProjectDoesntExist { .. } => 1,
+                    ProjectError::InvalidName { .. } => 2,
+                    ProjectError::NoActiveProject => 3,
+                    ProjectError::BackupDoesntExist { .. } => 4,
+                    ProjectError::TrashEntryDoesntExist { .. } => 5,
+                    ProjectError::TemplateDoesntExist { .. } => 6,
+                    ProjectError::Locked { .. } => 7,
+                    ProjectError::ReadOnly { .. } => 8,
+                    ProjectError::OpenInOtherWindow { .. } => 9,
+                    ProjectError::RecordDoesntExist { .. } => 10,
+                    ProjectError::InvalidRecord { .. } => 11,
+                    ProjectError::InvalidArchive { .. } => 12,
+                    ProjectError::UnsupportedSchema { .. } => 13
+                }
+            ),
+            Error::Command { error: CommandError::AssetVersion { error }, .. } => (
+                "asset_versions",
+                match error {
+                    AssetVersionError::InvalidName { .. } => 0,
+                    AssetVersionError::InvalidPack { .. } => 1,
+                    AssetVersionError::AlreadyInstalled { .. } => 2,
+                    AssetVersionError::NotInstalled { .. } => 3,
+                    AssetVersionError::Bundled { .. } => 4,
+                    AssetVersionError::InUse { .. } => 5
+                }
+            ),
+            Error::Command { error: CommandError::Settings { error }, .. } => (
+                "settings",
+                match error {
+                    SettingsError::InvalidProjectPath { .. } => 0,
+                    SettingsError::InvalidLocale { .. } => 1,
+                    SettingsError::UnknownAssetVersion { .. } => 2
+                }
+            ),
+            Error::Persistence { error, .. } => (
+                "persistence",
+                match error {
+                    Persistence::Unknown { .. } => 0,
+                    Persistence::OpenContext { .. } => 1,
+                    Persistence::OpenDatabase { .. } => 2,
+                    Persistence::OpenFileHandle { .. } => 3,
+                    Persistence::UnknownContext { .. } => 4,
+                    Persistence::UnknownDatabase { .. } => 5,
+                    Persistence::UnknownFileHandle { .. } => 6,
+                    Persistence::UnknownTransaction { .. } => 7,
+                    Persistence::InvalidPath { .. } => 8,
+                    Persistence::NoAbsolutePaths { .. } => 9,
+                    Persistence::PathEscapesContext { .. } => 10,
+                    Persistence::DatabaseError { .. } => 11,
+                    Persistence::SerializationError { .. } => 12,
+                    Persistence::DeserializationError { .. } => 13,
+                    Persistence::IOError { .. } => 14,
+                    Persistence::StringEncodingError { .. } => 15,
+                    Persistence::FilesystemError { .. } => 16
+                }
+            ),
+            // Tauri's own errors share one code; orbital_common covers its errors itself.
+            Error::Tauri { .. } => ("tauri", 0),
+            Error::CommonInternals { .. } => ("docs", 0)
+        }
+    }
+
+    #[test]
+    fn every_variant_is_sampled() {
+        let mut sampled: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for error in every_error() {
+            let (category, index) = variant(&error);
+            sampled.entry(category).or_default().push(index);
+        }
+
+        for (category, mut indices) in sampled {
+            indices.sort();
+            assert_eq!(indices, (0..indices.len()).collect::<Vec<_>>(), "{category} variants are missing or sampled twice");
+        }
+    }
+
+    #[test]
+    fn error_codes_are_unique() {
+        let errors = every_error();
+        let codes: Vec<&str> = errors.iter().map(|error| error.info().code.as_str()).collect();
+
+        for (i, code) in codes.iter().enumerate() {
+            assert!(!codes[i + 1..].contains(code), "duplicate code {code}");
+            assert!(code.starts_with(&format!("{}.", variant(&errors[i]).0)), "code {code} is outside its category");
+        }
+    }
+
+    #[test]
+    fn error_codes_are_localized() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/lang/en.json");
+        let messages: serde_json::Value = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+
+        for error in every_error() {
+            let info = error.info();
+            let pointer = format!("/{}", info.key.replace('.', "/"));
+            let Some(message) = messages.pointer(&pointer).and_then(|message| message.as_str()) else {
+                panic!("{} has no message in en.json", info.key);
+            };
+            for placeholder in message.split("{{").skip(1).filter_map(|rest| rest.split_once("}}")) {
+                assert!(info.params.contains_key(placeholder.0), "{} has no {} param", info.key, placeholder.0);
+            }
+        }
+    }
+}
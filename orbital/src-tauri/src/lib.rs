@@ -1,6 +1,8 @@
 mod commands;
 
-use commands::routes;
+use commands::{routes, ProjectsImpl};
+use tauri::{Manager, WindowEvent};
+use utils::{ProjectsExt, TrashExt};
 pub mod error;
 pub mod utils;
 
@@ -10,6 +12,10 @@ pub(crate) use error::*;
 #[tokio::main]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() {
+    let projects = ProjectsImpl::new();
+    let window_projects = projects.clone();
+    let backup_projects = projects.clone();
+    let watched_projects = projects.clone();
     let builder = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -17,9 +23,56 @@ pub async fn run() {
         .plugin(tauri_plugin_log::Builder::new().build())
         .plugin(tauri_plugin_persistence::init())
         .plugin(tauri_plugin_zustand::init())
-        .invoke_handler(routes())
-        .setup(|app| {
+        .register_asynchronous_uri_scheme_protocol(utils::protocol::SCHEME, utils::protocol::protocol_handler)
+        .invoke_handler(routes(projects))
+        .on_window_event(move |window, event| {
+            if let WindowEvent::Destroyed = event {
+                let projects = window_projects.clone();
+                let app_handle = window.app_handle().clone();
+                let label = window.label().to_string();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = projects.close_active(&app_handle, label).await {
+                        log::error!("Failed to close project of destroyed window: {e}");
+                    }
+                });
+            }
+        })
+        .setup(move |app| {
             utils::AppState::attach(app.handle());
+            utils::GameData::attach(app.handle());
+            utils::PackArchives::attach(app.handle());
+
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut timer = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    timer.tick().await;
+                    if let Err(e) = backup_projects.snapshot_open_projects(&handle).await {
+                        log::error!("Failed to snapshot open projects: {e}");
+                    }
+                }
+            });
+
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                watched_projects.watch_projects(&handle).await;
+            });
+
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle.migrate_legacy_projects().await {
+                    log::error!("Failed to migrate legacy projects: {e}");
+                }
+            });
+
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match handle.purge_expired_trash().await {
+                    Ok(purged) if !purged.is_empty() => log::info!("Purged {} expired trash entries", purged.len()),
+                    Ok(_) => (),
+                    Err(e) => log::error!("Failed to purge expired trash: {e}"),
+                }
+            });
 
             Ok(())
         });
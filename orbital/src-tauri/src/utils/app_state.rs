@@ -1,34 +1,193 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{fs, path::PathBuf, sync::Arc};
 
 use async_trait::async_trait;
 use bevy_reflect::{GetPath, Reflect};
 use derive_builder::Builder;
 use rust_patch::Patch;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{from_slice, to_string_pretty};
+use specta::Type;
 use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Reflect)]
+use super::AssetsExt;
+
+/// Number of entries kept in `AppState::recent_projects`.
+pub const RECENT_PROJECTS: usize = 10;
+
+/// Default for `AppState::history_depth`.
+pub const DEFAULT_HISTORY_DEPTH: u32 = 100;
+
+/// Default for `AppState::trash_retention_days`.
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// Defaults for the project snapshot settings (`AppState::backup_*`).
+pub const DEFAULT_BACKUP_COUNT: u32 = 10;
+pub const DEFAULT_BACKUP_MAX_AGE_DAYS: u32 = 30;
+pub const DEFAULT_BACKUP_INTERVAL_MINUTES: u32 = 15;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitsPreference {
+    PerSecond,
+    #[default]
+    PerMinute,
+    PerHour,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Reflect, Type)]
 pub struct AppState {
     pub project_path: PathBuf,
+    pub default_locale: String,
+    pub default_asset_version: Option<String>,
+    pub units: UnitsPreference,
+
+    /// Ids of recently opened projects, most recent first
+    pub recent_projects: Vec<String>,
+
+    /// Number of undoable commands kept per project. `0` disables the edit history.
+    pub history_depth: u32,
+
+    /// Days a removed project stays in the trash before it is purged. `0` keeps it until purged manually.
+    pub trash_retention_days: u32,
+
+    /// Snapshots kept per project. `0` keeps every snapshot.
+    pub backup_count: u32,
+
+    /// Days after which snapshots are rotated out. `0` disables rotation by age.
+    pub backup_max_age_days: u32,
+
+    /// Minutes between automatic snapshots of open projects. `0` disables timed snapshots.
+    pub backup_interval_minutes: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Builder, Patch)]
+/// Distinguishes an explicit `null` (clear the value) from a missing key (leave it unchanged).
+fn explicit_option<'de, T: Deserialize<'de>, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Builder, Patch, Type)]
 #[patch = "AppState"]
-#[builder(name = "StatePatch", setter(into, strip_option))]
+#[builder(name = "StatePatch", setter(into, strip_option), default)]
 pub struct AppStatePatcher {
+    #[serde(default)]
     pub project_path: Option<PathBuf>,
+
+    #[serde(default)]
+    pub default_locale: Option<String>,
+
+    #[serde(default, deserialize_with = "explicit_option")]
+    pub default_asset_version: Option<Option<String>>,
+
+    #[serde(default)]
+    pub units: Option<UnitsPreference>,
+
+    #[serde(default)]
+    pub recent_projects: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub history_depth: Option<u32>,
+
+    #[serde(default)]
+    pub trash_retention_days: Option<u32>,
+
+    #[serde(default)]
+    pub backup_count: Option<u32>,
+
+    #[serde(default)]
+    pub backup_max_age_days: Option<u32>,
+
+    #[serde(default)]
+    pub backup_interval_minutes: Option<u32>,
 }
 
 impl AppState {
-    pub fn attach<R: tauri::Runtime>(handle: &AppHandle<R>) {
-        let resolver = handle.path();
-
-        handle.manage(Arc::new(Mutex::new(Self {
-            project_path: resolver
+    fn defaults<R: tauri::Runtime>(handle: &AppHandle<R>) -> Self {
+        Self {
+            project_path: handle
+                .path()
                 .app_local_data_dir()
                 .expect("Should be able to retrieve app local data dir."),
-        })));
+            default_locale: String::from("en-US"),
+            default_asset_version: None,
+            units: UnitsPreference::default(),
+            recent_projects: Vec::new(),
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            backup_max_age_days: DEFAULT_BACKUP_MAX_AGE_DAYS,
+            backup_interval_minutes: DEFAULT_BACKUP_INTERVAL_MINUTES,
+        }
+    }
+
+    pub fn settings_path<R: tauri::Runtime>(handle: &AppHandle<R>) -> PathBuf {
+        handle
+            .path()
+            .app_config_dir()
+            .expect("Should be able to retrieve app config dir.")
+            .join("settings.json")
+    }
+
+    /// Loads persisted settings on top of the defaults. Settings written by older versions may lack keys, so the
+    /// file is read as a patch.
+    pub fn attach<R: tauri::Runtime>(handle: &AppHandle<R>) {
+        let defaults = Self::defaults(handle);
+        let state = match fs::read(Self::settings_path(handle)) {
+            Ok(data) => match from_slice::<AppStatePatcher>(data.as_slice()) {
+                Ok(patch) => patch.apply(defaults),
+                Err(e) => {
+                    log::error!("Ignoring invalid settings file: {e}");
+                    defaults
+                }
+            },
+            Err(_) => defaults,
+        };
+
+        handle.manage(Arc::new(Mutex::new(state)));
+    }
+
+    pub fn save<R: tauri::Runtime>(&self, handle: &AppHandle<R>) -> crate::Result<()> {
+        let path = Self::settings_path(handle);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).or_else(|e| Err(crate::Error::from(e)))?;
+        }
+
+        fs::write(path, to_string_pretty(self).or_else(|e| Err(crate::Error::serialization(e)))?)
+            .or_else(|e| Err(crate::Error::from(e)))
+    }
+
+}
+
+impl AppStatePatcher {
+    /// Validates the values this patch sets. Settings it leaves alone are not checked, so a value that became
+    /// invalid on its own (i.e. an uninstalled asset version) doesn't block unrelated changes.
+    pub fn validate<R: tauri::Runtime>(&self, handle: &AppHandle<R>) -> crate::Result<()> {
+        if let Some(project_path) = self.project_path.as_ref() {
+            if !project_path.is_absolute() {
+                return Err(crate::SettingsError::invalid_project_path(project_path.as_path(), "Path must be absolute"));
+            }
+            if project_path.exists() && !project_path.is_dir() {
+                return Err(crate::SettingsError::invalid_project_path(project_path.as_path(), "Path is not a folder"));
+            }
+        }
+
+        if let Some(locale) = self.default_locale.as_ref() {
+            let mut parts = locale.split('-');
+            let language_valid = parts
+                .next()
+                .is_some_and(|lang| (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase()));
+            if !language_valid || !parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric())) {
+                return Err(crate::SettingsError::invalid_locale(locale.clone()));
+            }
+        }
+
+        if let Some(Some(version)) = self.default_asset_version.clone() {
+            if !handle.asset_versions()?.contains(&version) {
+                return Err(crate::SettingsError::unknown_asset_version(version));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -39,7 +198,9 @@ pub trait AppStateExt<R: tauri::Runtime> {
         &self,
         path: impl AsRef<str> + Send + Sync,
     ) -> Option<V>;
-    async fn patch_app_state(&self, patch: StatePatch) -> AppState;
+    async fn patch_app_state(&self, patch: StatePatch) -> crate::Result<AppState>;
+    async fn apply_app_state_patch(&self, patch: AppStatePatcher) -> crate::Result<AppState>;
+    async fn push_recent_project(&self, id: String) -> crate::Result<AppState>;
 }
 
 #[async_trait]
@@ -50,11 +211,32 @@ impl<R: tauri::Runtime, T: Manager<R> + Send + Sync> AppStateExt<R> for T {
         locked.clone()
     }
 
-    async fn patch_app_state(&self, patch: StatePatch) -> AppState {
+    async fn patch_app_state(&self, patch: StatePatch) -> crate::Result<AppState> {
+        self.apply_app_state_patch(patch.build().unwrap()).await
+    }
+
+    async fn apply_app_state_patch(&self, patch: AppStatePatcher) -> crate::Result<AppState> {
         let state = self.state::<Arc<Mutex<AppState>>>();
         let mut locked = state.lock().await;
-        *locked = patch.build().unwrap().apply(locked.clone());
-        locked.clone()
+        patch.validate(self.app_handle())?;
+        let patched = patch.apply(locked.clone());
+
+        if !patched.project_path.exists() {
+            fs::create_dir_all(patched.project_path.clone()).or_else(|e| Err(crate::Error::from(e)))?;
+        }
+        patched.save(self.app_handle())?;
+        *locked = patched;
+        Ok(locked.clone())
+    }
+
+    async fn push_recent_project(&self, id: String) -> crate::Result<AppState> {
+        let mut recent = self.app_state().await.recent_projects;
+        recent.retain(|existing| existing != &id);
+        recent.insert(0, id);
+        recent.truncate(RECENT_PROJECTS);
+        let mut patch = StatePatch::default();
+        patch.recent_projects(recent);
+        self.patch_app_state(patch).await
     }
 
     async fn app_state_key<V: Send + Sync + Reflect + Clone>(
@@ -0,0 +1,311 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, to_string_pretty};
+use specta::Type;
+use tauri::Manager;
+use uuid::Uuid;
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use super::{
+    assets::{AssetVersionReference, AssetsExt},
+    projects::SCHEMA_VERSION,
+    ProjectConfig, ProjectsExt,
+};
+
+/// Files of a project directory that are included in an `.orbital` archive.
+const ARCHIVED_FILES: [&str; 3] = ["project.json", "project.db", "records"];
+const MANIFEST_FILE: &str = "manifest.json";
+const ARCHIVE_FORMAT: u32 = 1;
+
+/// Prefix of the hidden folder an archive is unpacked into before it is moved into place under its project id.
+const IMPORT_PREFIX: &str = ".import-";
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ArchiveManifest {
+    pub format: u32,
+    pub orbital_version: String,
+    pub exported: DateTime<Utc>,
+    pub asset_version: AssetVersionReference,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportWarning {
+    /// The archived project's id was already in use, so it was imported under a new one.
+    IdConflict { original_id: String, new_id: String },
+
+    /// The asset version the project was made with isn't installed.
+    MissingAssetVersion { name: String },
+
+    /// An asset version with the same name is installed, but its data differs from the exported one.
+    AssetVersionMismatch { name: String, expected_hash: String, installed_hash: String },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ProjectImport {
+    pub config: ProjectConfig,
+    pub warnings: Vec<ImportWarning>,
+}
+
+fn archive_error(reason: impl ToString) -> crate::Error {
+    crate::ProjectError::invalid_archive(reason.to_string())
+}
+
+fn read_entry(archive: &mut ZipArchive<fs::File>, name: &str) -> crate::Result<Vec<u8>> {
+    let mut entry = archive.by_name(name).or_else(|e| Err(archive_error(format!("{name}: {e}"))))?;
+    let mut buf: Vec<u8> = Vec::new();
+    entry.read_to_end(&mut buf).or_else(|e| Err(crate::Error::from(e)))?;
+    Ok(buf)
+}
+
+/// Extracts the project files of an archive into `target`, followed by `config` as its `project.json`.
+fn unpack(archive: &mut ZipArchive<fs::File>, target: &Path, config: &ProjectConfig) -> crate::Result<()> {
+    fs::create_dir_all(target).or_else(|e| Err(crate::Error::from(e)))?;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).or_else(|e| Err(archive_error(e)))?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        if !ARCHIVED_FILES[1..].iter().any(|archived| relative.starts_with(Path::new(archived))) {
+            continue;
+        }
+
+        let destination = target.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(destination).or_else(|e| Err(crate::Error::from(e)))?;
+        } else {
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).or_else(|e| Err(crate::Error::from(e)))?;
+            }
+            let mut out = fs::File::create(destination).or_else(|e| Err(crate::Error::from(e)))?;
+            std::io::copy(&mut entry, &mut out).or_else(|e| Err(crate::Error::from(e)))?;
+        }
+    }
+
+    fs::write(
+        target.join("project.json"),
+        to_string_pretty(config).or_else(|e| Err(crate::Error::serialization(e)))?,
+    )
+    .or_else(|e| Err(crate::Error::from(e)))
+}
+
+/// Writes `manifest` and the project files of `directory` into a new archive at `path`.
+fn pack(directory: &Path, path: &Path, manifest: &ArchiveManifest) -> crate::Result<()> {
+    let file = fs::File::create(path).or_else(|e| Err(crate::Error::from(e)))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    writer.start_file(MANIFEST_FILE, options).or_else(|e| Err(archive_error(e)))?;
+    writer
+        .write_all(to_string_pretty(manifest).or_else(|e| Err(crate::Error::serialization(e)))?.as_bytes())
+        .or_else(|e| Err(crate::Error::from(e)))?;
+
+    for archived in ARCHIVED_FILES {
+        for entry in WalkDir::new(directory.join(archived)).into_iter().filter_map(|e| e.ok()) {
+            let relative = entry
+                .path()
+                .strip_prefix(directory)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/");
+            if entry.path().is_dir() {
+                writer.add_directory(relative, options).or_else(|e| Err(archive_error(e)))?;
+            } else {
+                writer.start_file(relative, options).or_else(|e| Err(archive_error(e)))?;
+                writer
+                    .write_all(fs::read(entry.path()).or_else(|e| Err(crate::Error::from(e)))?.as_slice())
+                    .or_else(|e| Err(crate::Error::from(e)))?;
+            }
+        }
+    }
+
+    writer.finish().or_else(|e| Err(archive_error(e)))?;
+    Ok(())
+}
+
+/// Imports the archive at `path` as a new project in `projects_dir`. `installed` gives the installed asset version
+/// of a name, if any, to check the archive's asset version against.
+fn import(
+    path: &Path,
+    projects_dir: &Path,
+    installed: impl Fn(&str) -> Option<AssetVersionReference>,
+) -> crate::Result<ProjectImport> {
+    let file = fs::File::open(path).or_else(|e| Err(crate::Error::from(e)))?;
+    let mut archive = ZipArchive::new(file).or_else(|e| Err(archive_error(e)))?;
+
+    let manifest = from_slice::<ArchiveManifest>(read_entry(&mut archive, MANIFEST_FILE)?.as_slice())
+        .or_else(|e| Err(crate::Error::deserialization(e)))?;
+    if manifest.format > ARCHIVE_FORMAT {
+        return Err(archive_error(format!("Unsupported archive format {}", manifest.format)));
+    }
+
+    let mut config = from_slice::<ProjectConfig>(read_entry(&mut archive, "project.json")?.as_slice())
+        .or_else(|e| Err(crate::Error::deserialization(e)))?;
+    if config.schema_version > SCHEMA_VERSION {
+        return Err(crate::ProjectError::unsupported_schema(config.id, config.schema_version));
+    }
+
+    let mut warnings: Vec<ImportWarning> = Vec::new();
+    if config.is_legacy() || projects_dir.join(config.id.clone()).exists() {
+        let new_id = Uuid::new_v4().to_string();
+        warnings.push(ImportWarning::IdConflict { original_id: config.id.clone(), new_id: new_id.clone() });
+        config.id = new_id;
+    }
+
+    let target = projects_dir.join(config.id.clone());
+    let staging = projects_dir.join(format!("{IMPORT_PREFIX}{}", config.id));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).or_else(|e| Err(crate::Error::from(e)))?;
+    }
+    let unpacked = unpack(&mut archive, &staging, &config)
+        .and_then(|_| fs::rename(&staging, &target).or_else(|e| Err(crate::Error::from(e))));
+    if let Err(e) = unpacked {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let expected = manifest.asset_version;
+    match installed(expected.name.as_str()) {
+        Some(installed) => {
+            if !expected.hash.is_empty() && installed.hash != expected.hash {
+                warnings.push(ImportWarning::AssetVersionMismatch {
+                    name: expected.name,
+                    expected_hash: expected.hash,
+                    installed_hash: installed.hash,
+                });
+            }
+        }
+        None => warnings.push(ImportWarning::MissingAssetVersion { name: expected.name }),
+    }
+
+    Ok(ProjectImport { config, warnings })
+}
+
+#[async_trait::async_trait]
+pub trait ArchiveExt {
+    async fn export_project(&self, id: String, path: PathBuf) -> crate::Result<ArchiveManifest>;
+    async fn import_project(&self, path: PathBuf) -> crate::Result<ProjectImport>;
+}
+
+#[async_trait::async_trait]
+impl<T: Manager<tauri::Wry> + Send + Sync> ArchiveExt for T {
+    async fn export_project(&self, id: String, path: PathBuf) -> crate::Result<ArchiveManifest> {
+        let directory = self.projects_directory().await.join(id.clone());
+        if !directory.join("project.json").exists() {
+            return Err(crate::ProjectError::not_exists(id));
+        }
+
+        let config = from_slice::<ProjectConfig>(
+            fs::read(directory.join("project.json")).or_else(|e| Err(crate::Error::from(e)))?.as_slice(),
+        )
+        .or_else(|e| Err(crate::Error::deserialization(e)))?;
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT,
+            orbital_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported: Utc::now(),
+            asset_version: match self.asset_version_reference(config.asset_version.clone()) {
+                Ok(reference) => reference,
+                Err(_) => AssetVersionReference { name: config.asset_version.clone(), hash: String::new() },
+            },
+        };
+
+        pack(&directory, &path, &manifest)?;
+        Ok(manifest)
+    }
+
+    async fn import_project(&self, path: PathBuf) -> crate::Result<ProjectImport> {
+        let projects_dir = self.projects_directory().await;
+        import(&path, &projects_dir, |name| self.asset_version_reference(name).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(hash: &str) -> AssetVersionReference {
+        AssetVersionReference { name: String::from("1.0-stable"), hash: hash.to_string() }
+    }
+
+    /// Writes a project with a record and some files that don't belong in an archive, and exports it.
+    fn exported(root: &Path) -> (ProjectConfig, PathBuf) {
+        let config = ProjectConfig::new("Exported", "1.0-stable");
+        let directory = root.join("source");
+        fs::create_dir_all(directory.join("records").join("factories")).unwrap();
+        fs::create_dir_all(directory.join("backups").join("old")).unwrap();
+        fs::write(directory.join("project.json"), to_string_pretty(&config).unwrap()).unwrap();
+        fs::write(directory.join("records").join("factories").join("a.json"), b"{\"id\": \"a\"}").unwrap();
+        fs::write(directory.join("backups").join("old").join("project.json"), b"{}").unwrap();
+
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT,
+            orbital_version: String::from("test"),
+            exported: Utc::now(),
+            asset_version: reference("docs-hash"),
+        };
+        let path = root.join("exported.orbital");
+        pack(&directory, &path, &manifest).unwrap();
+        (config, path)
+    }
+
+    fn hidden_folders(projects: &Path) -> usize {
+        fs::read_dir(projects)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .count()
+    }
+
+    #[test]
+    fn round_trip() {
+        let root = tempfile::tempdir().unwrap();
+        let (config, path) = exported(root.path());
+        let projects = root.path().join("projects");
+        fs::create_dir_all(projects.join(format!("{IMPORT_PREFIX}{}", config.id))).unwrap();
+
+        let imported = import(&path, &projects, |_| Some(reference("docs-hash"))).unwrap();
+        assert_eq!(imported.config.id, config.id);
+        assert!(imported.warnings.is_empty());
+
+        let directory = projects.join(&config.id);
+        let written = from_slice::<ProjectConfig>(fs::read(directory.join("project.json")).unwrap().as_slice()).unwrap();
+        assert_eq!(written.name, "Exported");
+        assert_eq!(fs::read(directory.join("records").join("factories").join("a.json")).unwrap(), b"{\"id\": \"a\"}");
+        assert!(!directory.join("backups").exists());
+        assert_eq!(hidden_folders(&projects), 0);
+    }
+
+    #[test]
+    fn import_warnings() {
+        let root = tempfile::tempdir().unwrap();
+        let (config, path) = exported(root.path());
+        let projects = root.path().join("projects");
+        import(&path, &projects, |_| Some(reference("docs-hash"))).unwrap();
+
+        let conflicting = import(&path, &projects, |_| Some(reference("docs-hash"))).unwrap();
+        assert_ne!(conflicting.config.id, config.id);
+        assert!(matches!(
+            conflicting.warnings.as_slice(),
+            [ImportWarning::IdConflict { original_id, new_id }] if original_id == &config.id && new_id == &conflicting.config.id
+        ));
+        assert!(projects.join(&conflicting.config.id).join("project.json").is_file());
+
+        let missing = import(&path, &projects, |_| None).unwrap();
+        assert!(matches!(missing.warnings.last(), Some(ImportWarning::MissingAssetVersion { name }) if name == "1.0-stable"));
+
+        let mismatched = import(&path, &projects, |_| Some(reference("other-hash"))).unwrap();
+        assert!(matches!(
+            mismatched.warnings.last(),
+            Some(ImportWarning::AssetVersionMismatch { expected_hash, installed_hash, .. })
+                if expected_hash == "docs-hash" && installed_hash == "other-hash"
+        ));
+        assert_eq!(hidden_folders(&projects), 0);
+    }
+}
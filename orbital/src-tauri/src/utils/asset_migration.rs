@@ -0,0 +1,165 @@
+use std::collections::{BTreeSet, HashMap};
+
+use orbital_common::types::satisfactory::{OrbitalData, RecipeItem};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::{LineLink, LoadedData, ProductionLine, Project, Record};
+
+/// What switching a project between asset versions affects. Only entities the project actually uses are listed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+pub struct AssetMigrationReport {
+    pub from: String,
+    pub to: String,
+    pub removed_recipes: Vec<String>,
+    pub changed_recipes: Vec<String>,
+    pub removed_items: Vec<String>,
+    pub changed_items: Vec<String>,
+    pub removed_buildings: Vec<String>,
+    pub changed_buildings: Vec<String>,
+
+    /// Production lines that were marked as needing review
+    pub flagged_lines: Vec<String>,
+}
+
+impl AssetMigrationReport {
+    fn affects(&self, ids: &BTreeSet<String>) -> bool {
+        [
+            &self.removed_recipes,
+            &self.changed_recipes,
+            &self.removed_items,
+            &self.changed_items,
+            &self.removed_buildings,
+            &self.changed_buildings,
+        ]
+        .iter()
+        .any(|list| list.iter().any(|id| ids.contains(id)))
+    }
+}
+
+/// Splits the `used` ids into those missing from `to` and those whose definition differs between versions.
+fn diff<T: PartialEq>(used: &BTreeSet<String>, from: &HashMap<String, T>, to: &HashMap<String, T>) -> (Vec<String>, Vec<String>) {
+    let mut removed: Vec<String> = Vec::new();
+    let mut changed: Vec<String> = Vec::new();
+    for id in used {
+        match (from.get(id), to.get(id)) {
+            (Some(_), None) => removed.push(id.clone()),
+            (Some(old), Some(new)) if old != new => changed.push(id.clone()),
+            _ => (),
+        }
+    }
+
+    (removed, changed)
+}
+
+fn recipe_items(recipe: &RecipeItem) -> impl Iterator<Item = String> + '_ {
+    recipe
+        .ingredients
+        .as_ref()
+        .iter()
+        .flatten()
+        .chain(recipe.product.as_ref().iter().flatten())
+        .map(|reference| reference.item.id().to_string())
+}
+
+/// Every entity id a production line depends on in `data`: its recipe, machines and the recipe's items.
+fn line_dependencies(line: &ProductionLine, data: &OrbitalData) -> BTreeSet<String> {
+    let mut ids = BTreeSet::from([line.recipe.clone()]);
+    if let Some(machine) = line.machine.clone() {
+        ids.insert(machine);
+    }
+    if let Some(recipe) = data.recipes.get(&line.recipe) {
+        ids.extend(recipe_items(recipe));
+        if line.machine.is_none() {
+            ids.extend(recipe.machine.as_ref().iter().flatten().map(|m| m.id().to_string()));
+        }
+    }
+    ids
+}
+
+impl Project {
+    /// Compares the project's usage of `from` against `to` and flags every affected production line for review.
+    pub async fn migrate_asset_version(&self, from: &LoadedData, to: &LoadedData) -> crate::Result<AssetMigrationReport> {
+        let lines = self.records::<ProductionLine>().await?;
+        let links = self.records::<LineLink>().await?;
+
+        let mut recipes: BTreeSet<String> = BTreeSet::new();
+        let mut items: BTreeSet<String> = links.iter().map(|link| link.item.clone()).collect();
+        let mut buildings: BTreeSet<String> = BTreeSet::new();
+        for line in lines.iter() {
+            for id in line_dependencies(line, &from.data) {
+                if from.data.recipes.contains_key(&id) {
+                    recipes.insert(id);
+                } else if from.data.buildables.contains_key(&id) {
+                    buildings.insert(id);
+                } else {
+                    items.insert(id);
+                }
+            }
+        }
+
+        let (removed_recipes, changed_recipes) = diff(&recipes, &from.data.recipes, &to.data.recipes);
+        let (removed_items, changed_items) = diff(&items, &from.data.descriptions, &to.data.descriptions);
+        let (removed_buildings, changed_buildings) = diff(&buildings, &from.data.buildables, &to.data.buildables);
+        let mut report = AssetMigrationReport {
+            from: from.version.clone(),
+            to: to.version.clone(),
+            removed_recipes,
+            changed_recipes,
+            removed_items,
+            changed_items,
+            removed_buildings,
+            changed_buildings,
+            flagged_lines: Vec::new(),
+        };
+
+        for line in lines {
+            let mut dependencies = line_dependencies(&line, &from.data);
+            dependencies.extend(
+                links
+                    .iter()
+                    .filter(|link| link.source.as_ref() == Some(&line.id) || link.target.as_ref() == Some(&line.id))
+                    .map(|link| link.item.clone()),
+            );
+
+            if report.affects(&dependencies) {
+                report.flagged_lines.push(line.id());
+                self.save_record(ProductionLine { needs_review: true, ..line }).await?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_used_ids() {
+        let used = BTreeSet::from(["kept".to_string(), "changed".to_string(), "removed".to_string(), "new".to_string()]);
+        let from = HashMap::from([
+            ("kept".to_string(), 1),
+            ("changed".to_string(), 1),
+            ("removed".to_string(), 1),
+            ("unused".to_string(), 1),
+        ]);
+        let to = HashMap::from([("kept".to_string(), 1), ("changed".to_string(), 2), ("new".to_string(), 1)]);
+
+        assert_eq!(diff(&used, &from, &to), (vec!["removed".to_string()], vec!["changed".to_string()]));
+    }
+
+    #[test]
+    fn report_affects_listed_ids() {
+        let report = AssetMigrationReport {
+            removed_items: vec!["Desc_OreIron_C".to_string()],
+            changed_buildings: vec!["Build_SmelterMk1_C".to_string()],
+            ..Default::default()
+        };
+
+        assert!(report.affects(&BTreeSet::from(["Desc_OreIron_C".to_string()])));
+        assert!(report.affects(&BTreeSet::from(["Recipe_IngotIron_C".to_string(), "Build_SmelterMk1_C".to_string()])));
+        assert!(!report.affects(&BTreeSet::from(["Recipe_IngotIron_C".to_string()])));
+    }
+}
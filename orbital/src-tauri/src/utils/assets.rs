@@ -0,0 +1,336 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use orbital_common::types::satisfactory::{AssetReference, Generated};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use tauri::{Manager, Runtime};
+use zip::ZipArchive;
+
+/// Identifies an asset version by name and by the hash of its `docs.json`.
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct AssetVersionReference {
+    pub name: String,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct AssetInstallReport {
+    pub name: String,
+    pub icons: usize,
+
+    /// Icons referenced by `docs.json` that the pack doesn't contain
+    pub missing_icons: Vec<String>,
+}
+
+/// Where an asset version's files live: an extracted pack folder, or a pack archive used in place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssetSource {
+    Directory(PathBuf),
+    Archive(PathBuf),
+}
+
+impl AssetSource {
+    pub fn path(&self) -> PathBuf {
+        match self {
+            AssetSource::Directory(path) => path.clone(),
+            AssetSource::Archive(path) => path.clone(),
+        }
+    }
+
+    /// Reads a file from the pack by its path relative to the pack root, returning `None` if it doesn't exist.
+    /// Paths that could leave the pack root are rejected.
+    pub fn read(&self, relative: impl AsRef<str>) -> crate::Result<Option<Vec<u8>>> {
+        let inside = !relative.as_ref().contains('\\')
+            && Path::new(relative.as_ref()).components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            return Err(crate::OperationError::invalid_path(relative.as_ref(), crate::InvalidPathType::Format));
+        }
+
+        match self {
+            AssetSource::Directory(path) => {
+                let target = path.join(relative.as_ref());
+                if target.is_file() {
+                    Ok(Some(fs::read(target).or_else(|e| Err(crate::Error::from(e)))?))
+                } else {
+                    Ok(None)
+                }
+            }
+            AssetSource::Archive(path) => {
+                let file = fs::File::open(path).or_else(|e| Err(crate::Error::from(e)))?;
+                let mut archive = ZipArchive::new(file)
+                    .or_else(|e| Err(crate::AssetVersionError::invalid_pack(e.to_string())))?;
+                read_archive_entry(&mut archive, relative)
+            }
+        }
+    }
+}
+
+/// Reads an entry from a pack archive. `aextract` writes entries relative to `./`, so both forms are accepted.
+pub fn read_archive_entry(archive: &mut ZipArchive<fs::File>, relative: impl AsRef<str>) -> crate::Result<Option<Vec<u8>>> {
+    let relative = relative.as_ref().trim_start_matches("./");
+    for name in [relative.to_string(), format!("./{relative}")] {
+        if let Ok(mut entry) = archive.by_name(name.as_str()) {
+            let mut buf: Vec<u8> = Vec::new();
+            entry.read_to_end(&mut buf).or_else(|e| Err(crate::Error::from(e)))?;
+            return Ok(Some(buf));
+        }
+    }
+
+    Ok(None)
+}
+
+pub trait AssetsExt<R: Runtime> {
+    fn bundled_assets_directory(&self) -> crate::Result<PathBuf>;
+    fn installed_assets_directory(&self) -> crate::Result<PathBuf>;
+    fn asset_versions(&self) -> crate::Result<Vec<String>>;
+    fn is_bundled_asset_version(&self, name: impl AsRef<str>) -> bool;
+    fn asset_source(&self, name: impl AsRef<str>) -> crate::Result<AssetSource>;
+    fn asset_version_reference(&self, name: impl AsRef<str>) -> crate::Result<AssetVersionReference>;
+    fn install_asset_version(&self, archive: impl AsRef<Path>, name: impl AsRef<str>) -> crate::Result<AssetInstallReport>;
+}
+
+fn list_versions(directory: &Path) -> crate::Result<Vec<String>> {
+    let read_dir = fs::read_dir(directory).or_else(|e| Err(crate::Error::from(e)))?;
+
+    Ok(read_dir.filter_map(|entry| {
+        if let Ok(e) = entry {
+            find_source(directory, e.file_name().to_str()?.trim_end_matches(".zip"))
+                .map(|_| e.file_name().into_string().unwrap().trim_end_matches(".zip").to_string())
+        } else {
+            None
+        }
+    }).collect())
+}
+
+fn find_source(directory: &Path, name: &str) -> Option<AssetSource> {
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return None;
+    }
+
+    let folder = directory.join(name);
+    if folder.join("docs.json").is_file() {
+        return Some(AssetSource::Directory(folder));
+    }
+
+    let archive = directory.join(format!("{name}.zip"));
+    if archive.is_file() {
+        return Some(AssetSource::Archive(archive));
+    }
+
+    None
+}
+
+/// Icon ids referenced by a pack's descriptions, matching what `aextract` requests from the game files.
+fn referenced_icons(generated: &Generated) -> HashSet<String> {
+    generated
+        .descriptions
+        .values()
+        .flat_map(|desc| [desc.icon.clone(), desc.big_icon.clone()])
+        .filter_map(|icon| match icon {
+            Some(AssetReference { asset_type, asset_id: Some(id), .. }) if asset_type == "Texture2D" => Some(id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks the pack archive at `archive` and extracts it into `installed` as asset version `name`.
+fn install_pack(archive: &Path, installed: &Path, name: String) -> crate::Result<AssetInstallReport> {
+    let file = fs::File::open(archive).or_else(|e| Err(crate::Error::from(e)))?;
+    let mut zipfile = ZipArchive::new(file).or_else(|e| Err(crate::AssetVersionError::invalid_pack(e.to_string())))?;
+
+    let docs = read_archive_entry(&mut zipfile, "docs.json")?
+        .ok_or(crate::AssetVersionError::invalid_pack("Missing docs.json"))?;
+    let generated = serde_json::from_slice::<Generated>(docs.as_slice())
+        .or_else(|e| Err(crate::AssetVersionError::invalid_pack(format!("Bad docs.json: {e}"))))?;
+
+    let icons: HashSet<String> = zipfile
+        .file_names()
+        .filter_map(|entry| {
+            entry
+                .trim_start_matches("./")
+                .strip_prefix("icons/")
+                .and_then(|icon| icon.strip_suffix(".png"))
+                .map(|icon| icon.to_string())
+        })
+        .collect();
+    if icons.is_empty() {
+        return Err(crate::AssetVersionError::invalid_pack("Pack contains no icons"));
+    }
+    let mut missing_icons: Vec<String> = referenced_icons(&generated).difference(&icons).cloned().collect();
+    missing_icons.sort();
+
+    let staging = installed.join(format!(".{name}.partial"));
+    if staging.exists() {
+        fs::remove_dir_all(staging.clone()).or_else(|e| Err(crate::Error::from(e)))?;
+    }
+    zipfile
+        .extract(staging.clone())
+        .or_else(|e| Err(crate::AssetVersionError::invalid_pack(e.to_string())))?;
+    fs::rename(staging, installed.join(name.clone())).or_else(|e| Err(crate::Error::from(e)))?;
+
+    Ok(AssetInstallReport { name, icons: icons.len(), missing_icons })
+}
+
+fn validate_version_name(name: &str) -> crate::Result<()> {
+    if name.trim().is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':'])
+    {
+        return Err(crate::AssetVersionError::invalid_name(name));
+    }
+
+    Ok(())
+}
+
+impl<R: Runtime, T: Manager<R>> AssetsExt<R> for T {
+    fn bundled_assets_directory(&self) -> crate::Result<PathBuf> {
+        let asset_dir = self
+            .path()
+            .resource_dir()
+            .or_else(|e| Err(crate::Error::from(e)))?
+            .join("resources/assets");
+        if !asset_dir.exists() {
+            return Err(crate::OperationError::invalid_path(asset_dir.as_path(), crate::InvalidPathType::NotExists));
+        }
+        if !asset_dir.is_dir() {
+            return Err(crate::OperationError::invalid_path(asset_dir.as_path(), crate::InvalidPathType::ExpectedFolder));
+        }
+
+        Ok(asset_dir)
+    }
+
+    fn installed_assets_directory(&self) -> crate::Result<PathBuf> {
+        let asset_dir = self
+            .path()
+            .app_local_data_dir()
+            .or_else(|e| Err(crate::Error::from(e)))?
+            .join("assets");
+        if !asset_dir.exists() {
+            fs::create_dir_all(asset_dir.clone()).or_else(|e| Err(crate::Error::from(e)))?;
+        }
+
+        Ok(asset_dir)
+    }
+
+    fn asset_versions(&self) -> crate::Result<Vec<String>> {
+        let mut versions = list_versions(self.bundled_assets_directory()?.as_path())?;
+        versions.extend(list_versions(self.installed_assets_directory()?.as_path())?);
+
+        versions.sort();
+        versions.dedup();
+        Ok(versions)
+    }
+
+    fn is_bundled_asset_version(&self, name: impl AsRef<str>) -> bool {
+        self.bundled_assets_directory()
+            .is_ok_and(|dir| find_source(dir.as_path(), name.as_ref()).is_some())
+    }
+
+    fn asset_source(&self, name: impl AsRef<str>) -> crate::Result<AssetSource> {
+        if let Some(source) = find_source(self.bundled_assets_directory()?.as_path(), name.as_ref()) {
+            return Ok(source);
+        }
+
+        if let Some(source) = find_source(self.installed_assets_directory()?.as_path(), name.as_ref()) {
+            return Ok(source);
+        }
+
+        Err(crate::AssetVersionError::not_installed(name))
+    }
+
+    fn asset_version_reference(&self, name: impl AsRef<str>) -> crate::Result<AssetVersionReference> {
+        let docs = self
+            .asset_source(name.as_ref())?
+            .read("docs.json")?
+            .ok_or(crate::AssetVersionError::invalid_pack("Missing docs.json"))?;
+
+        Ok(AssetVersionReference {
+            name: name.as_ref().to_string(),
+            hash: format!("{:x}", Sha256::digest(docs)),
+        })
+    }
+
+    fn install_asset_version(&self, archive: impl AsRef<Path>, name: impl AsRef<str>) -> crate::Result<AssetInstallReport> {
+        let name = name.as_ref().to_string();
+        validate_version_name(name.as_str())?;
+        if self.asset_source(name.as_str()).is_ok() {
+            return Err(crate::AssetVersionError::already_installed(name));
+        }
+
+        install_pack(archive.as_ref(), &self.installed_assets_directory()?, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a pack archive holding `docs` as its `docs.json` and an empty icon for each of `icons`.
+    fn pack(path: &Path, docs: &[u8], icons: &[&str]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("./docs.json", options).unwrap();
+        std::io::Write::write_all(&mut writer, docs).unwrap();
+        for icon in icons {
+            writer.start_file(format!("./icons/{icon}.png"), options).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn install_bundled_docs() {
+        let docs = fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources/assets/1.0-stable/docs.json")).unwrap();
+        let referenced = referenced_icons(&serde_json::from_slice::<Generated>(docs.as_slice()).unwrap());
+        assert!(referenced.contains("IconDesc_IronPlates_256"));
+
+        let root = tempfile::tempdir().unwrap();
+        let archive = root.path().join("pack.zip");
+        pack(&archive, docs.as_slice(), &["IconDesc_IronPlates_256"]);
+        let installed = root.path().join("installed");
+        fs::create_dir_all(&installed).unwrap();
+
+        let report = install_pack(&archive, &installed, String::from("1.0-copy")).unwrap();
+        assert_eq!(report.icons, 1);
+        assert_eq!(report.missing_icons.len(), referenced.len() - 1);
+        assert!(!report.missing_icons.contains(&String::from("IconDesc_IronPlates_256")));
+        assert_eq!(fs::read(installed.join("1.0-copy").join("docs.json")).unwrap(), docs);
+        assert!(!installed.join(".1.0-copy.partial").exists());
+    }
+
+    #[test]
+    fn reject_invalid_packs() {
+        let root = tempfile::tempdir().unwrap();
+        let archive = root.path().join("pack.zip");
+
+        pack(&archive, b"{\"recipes\": 12}", &["IconDesc_IronPlates_256"]);
+        assert!(install_pack(&archive, root.path(), String::from("bad")).is_err());
+
+        let docs = fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../../resources/assets/1.0-stable/docs.json")).unwrap();
+        pack(&archive, docs.as_slice(), &[]);
+        assert!(install_pack(&archive, root.path(), String::from("bad")).is_err());
+        assert!(!root.path().join("bad").exists());
+    }
+
+    #[test]
+    fn read_stays_inside_pack() {
+        let root = tempfile::tempdir().unwrap();
+        let pack = root.path().join("1.0");
+        fs::create_dir_all(pack.join("icons")).unwrap();
+        fs::write(pack.join("icons").join("Desc_IronIngot_C.png"), b"icon").unwrap();
+        fs::write(root.path().join("secret.txt"), b"secret").unwrap();
+
+        let source = AssetSource::Directory(pack);
+        assert_eq!(source.read("icons/Desc_IronIngot_C.png").unwrap(), Some(b"icon".to_vec()));
+        assert_eq!(source.read("icons/Desc_Missing_C.png").unwrap(), None);
+        assert!(source.read("../secret.txt").is_err());
+        assert!(source.read("icons/../../secret.txt").is_err());
+        assert!(source.read("..\\secret.txt").is_err());
+        assert!(source.read(root.path().join("secret.txt").to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, to_string_pretty};
+use specta::Type;
+use tauri::Manager;
+use walkdir::WalkDir;
+
+use super::{
+    journal::{JOURNAL_FILE, PENDING_JOURNAL_FILE},
+    projects::copy_recursive,
+    AppStateExt, Project, ProjectLock, StorageFormat,
+};
+
+/// Folder inside each project that holds its snapshots.
+pub const BACKUPS_DIRECTORY: &str = "backups";
+
+/// Files of a project directory that are captured by a snapshot.
+const SNAPSHOT_FILES: [&str; 3] = ["project.json", "project.db", "records"];
+const SNAPSHOT_INFO_FILE: &str = "snapshot.json";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotReason {
+    Open,
+    Migration { from: u32 },
+    Timer,
+    Restore { backup: String },
+    Conversion { from: StorageFormat },
+}
+
+impl SnapshotReason {
+    fn slug(&self) -> String {
+        match self {
+            Self::Open => String::from("open"),
+            Self::Migration { from } => format!("pre-migration-v{from}"),
+            Self::Timer => String::from("timer"),
+            Self::Restore { .. } => String::from("pre-restore"),
+            Self::Conversion { .. } => String::from("pre-conversion"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct Backup {
+    /// Name of the snapshot's folder in `backups/`.
+    pub id: String,
+    pub reason: SnapshotReason,
+    pub created: DateTime<Utc>,
+    pub size: u64,
+}
+
+/// Copies `project.json` and the records of the project in `directory` into a new snapshot.
+pub fn snapshot(directory: impl AsRef<Path>, reason: SnapshotReason) -> crate::Result<Backup> {
+    let directory = directory.as_ref();
+    let created = Utc::now();
+    let id = format!("{}-{}", created.format("%Y%m%dT%H%M%S%3f"), reason.slug());
+    let target = directory.join(BACKUPS_DIRECTORY).join(id.clone());
+    fs::create_dir_all(&target).or_else(|e| Err(crate::Error::from(e)))?;
+
+    for file in SNAPSHOT_FILES {
+        if directory.join(file).exists() {
+            copy_recursive(directory.join(file), target.join(file))?;
+        }
+    }
+
+    let size = WalkDir::new(&target)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum();
+    let backup = Backup { id, reason, created, size };
+    fs::write(
+        target.join(SNAPSHOT_INFO_FILE),
+        to_string_pretty(&backup).or_else(|e| Err(crate::Error::serialization(e)))?,
+    )
+    .or_else(|e| Err(crate::Error::from(e)))?;
+
+    Ok(backup)
+}
+
+/// Lists the snapshots of the project in `directory`, newest first. Snapshots are named by their folder, whatever
+/// their info file says.
+pub fn backups(directory: impl AsRef<Path>) -> crate::Result<Vec<Backup>> {
+    let folder = directory.as_ref().join(BACKUPS_DIRECTORY);
+    if !folder.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut results: Vec<Backup> = Vec::new();
+    for entry in fs::read_dir(folder).or_else(|e| Err(crate::Error::from(e)))? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Ok(data) = fs::read(entry.path().join(SNAPSHOT_INFO_FILE)) else {
+            continue;
+        };
+        if let Ok(backup) = from_slice::<Backup>(data.as_slice()) {
+            results.push(Backup {
+                id: entry.file_name().to_string_lossy().to_string(),
+                ..backup
+            });
+        }
+    }
+
+    results.sort_by(|a, b| b.created.cmp(&a.created));
+    Ok(results)
+}
+
+/// Deletes snapshots beyond the newest `keep` and those older than `max_age_days`. Either limit is disabled by `0`.
+/// The newest snapshot is always kept.
+pub fn rotate(directory: impl AsRef<Path>, keep: u32, max_age_days: u32) -> crate::Result<Vec<String>> {
+    let cutoff = Utc::now() - Duration::days(max_age_days as i64);
+    let mut removed = Vec::new();
+
+    for (index, backup) in backups(&directory)?.into_iter().enumerate() {
+        let over_count = keep > 0 && index >= keep as usize;
+        let too_old = max_age_days > 0 && backup.created < cutoff;
+        if index > 0 && (over_count || too_old) {
+            fs::remove_dir_all(directory.as_ref().join(BACKUPS_DIRECTORY).join(backup.id.clone()))
+                .or_else(|e| Err(crate::Error::from(e)))?;
+            removed.push(backup.id);
+        }
+    }
+
+    Ok(removed)
+}
+
+impl Project {
+    /// Takes a snapshot of this open project and rotates its snapshots according to the backup settings. Waits for
+    /// the running command and closes `project.db` first, so the snapshot holds every write made so far.
+    pub async fn snapshot(&self, reason: SnapshotReason) -> crate::Result<Backup> {
+        let _running = self.history().pause().await;
+        self.flush_database().await?;
+
+        let settings = self.handle().app_state().await;
+        let backup = snapshot(self.directory(), reason)?;
+        rotate(self.directory(), settings.backup_count, settings.backup_max_age_days)?;
+        Ok(backup)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait BackupsExt {
+    async fn list_backups(&self, id: String, directory: PathBuf) -> crate::Result<Vec<Backup>>;
+    async fn restore_backup(&self, id: String, directory: PathBuf, backup: String) -> crate::Result<Backup>;
+}
+
+#[async_trait::async_trait]
+impl<T: Manager<tauri::Wry> + Send + Sync> BackupsExt for T {
+    /// Lists the snapshots of project `id`, which lives in `directory`.
+    async fn list_backups(&self, id: String, directory: PathBuf) -> crate::Result<Vec<Backup>> {
+        if !directory.join("project.json").exists() {
+            return Err(crate::ProjectError::not_exists(id));
+        }
+
+        backups(directory)
+    }
+
+    /// Replaces the files of project `id` in `directory` with a snapshot, snapshotting the current state first.
+    /// The project must not be open. Any autosave journal is dropped, since it describes edits on top of the
+    /// replaced database. Returns the snapshot taken of the replaced state.
+    async fn restore_backup(&self, id: String, directory: PathBuf, backup: String) -> crate::Result<Backup> {
+        ProjectLock::check(&id, &directory)?;
+        if !backups(&directory)?.iter().any(|existing| existing.id == backup) {
+            return Err(crate::ProjectError::backup_not_exists(id, backup));
+        }
+        let source = directory.join(BACKUPS_DIRECTORY).join(backup.clone());
+
+        // Rotate only once the restore is done, so the snapshot being restored cannot be rotated away.
+        let settings = self.app_state().await;
+        let safety = snapshot(&directory, SnapshotReason::Restore { backup: backup.clone() })?;
+
+        for file in SNAPSHOT_FILES {
+            let current: PathBuf = directory.join(file);
+            if current.is_dir() {
+                fs::remove_dir_all(&current).or_else(|e| Err(crate::Error::from(e)))?;
+            } else if current.exists() {
+                fs::remove_file(&current).or_else(|e| Err(crate::Error::from(e)))?;
+            }
+            if source.join(file).exists() {
+                copy_recursive(source.join(file), current)?;
+            }
+        }
+        for file in [JOURNAL_FILE, PENDING_JOURNAL_FILE] {
+            if directory.join(file).exists() {
+                fs::remove_file(directory.join(file)).or_else(|e| Err(crate::Error::from(e)))?;
+            }
+        }
+
+        rotate(&directory, settings.backup_count, settings.backup_max_age_days)?;
+        Ok(safety)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a snapshot created `days_ago` days ago.
+    fn backup(directory: &Path, name: &str, days_ago: i64) -> String {
+        let target = directory.join(BACKUPS_DIRECTORY).join(name);
+        fs::create_dir_all(&target).unwrap();
+        let backup = Backup {
+            id: name.to_string(),
+            reason: SnapshotReason::Timer,
+            created: Utc::now() - Duration::days(days_ago) - Duration::minutes(1),
+            size: 0,
+        };
+        fs::write(target.join(SNAPSHOT_INFO_FILE), to_string_pretty(&backup).unwrap()).unwrap();
+        name.to_string()
+    }
+
+    fn remaining(directory: &Path) -> Vec<String> {
+        backups(directory).unwrap().into_iter().map(|backup| backup.id).collect()
+    }
+
+    #[test]
+    fn rotate_by_count() {
+        let directory = tempfile::tempdir().unwrap();
+        let names: Vec<String> = (0..5).map(|days| backup(directory.path(), &format!("b{days}"), days)).collect();
+
+        assert_eq!(rotate(directory.path(), 3, 0).unwrap(), vec![names[3].clone(), names[4].clone()]);
+        assert_eq!(remaining(directory.path()), names[..3].to_vec());
+        assert!(rotate(directory.path(), 0, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rotate_by_age_keeps_newest() {
+        let directory = tempfile::tempdir().unwrap();
+        let old = backup(directory.path(), "old", 40);
+        let older = backup(directory.path(), "older", 50);
+
+        assert_eq!(rotate(directory.path(), 0, 30).unwrap(), vec![older]);
+        assert_eq!(remaining(directory.path()), vec![old.clone()]);
+
+        let recent = backup(directory.path(), "recent", 1);
+        assert_eq!(rotate(directory.path(), 10, 30).unwrap(), vec![old]);
+        assert_eq!(remaining(directory.path()), vec![recent]);
+    }
+
+    #[test]
+    fn snapshot_copies_project_files() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join("project.json"), b"{}").unwrap();
+        fs::create_dir_all(directory.path().join("records").join("factories")).unwrap();
+        fs::write(directory.path().join("records").join("factories").join("a.json"), b"{}").unwrap();
+        fs::write(directory.path().join(JOURNAL_FILE), b"").unwrap();
+
+        let created = snapshot(directory.path(), SnapshotReason::Open).unwrap();
+        let target = directory.path().join(BACKUPS_DIRECTORY).join(&created.id);
+        assert!(target.join("project.json").is_file());
+        assert!(target.join("records").join("factories").join("a.json").is_file());
+        assert!(!target.join(JOURNAL_FILE).exists());
+        assert_eq!(created.size, 4);
+        assert_eq!(remaining(directory.path()), vec![created.id]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::HashMap, io, sync::Arc};
+
+use orbital_common::{
+    types::satisfactory::{DataIndex, Generated, OrbitalData, SearchIndex},
+    DocsError,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::{Mutex, OnceCell};
+
+use super::AssetsExt;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum DataLoadStage {
+    Reading,
+    Parsing,
+    Indexing,
+    Ready,
+    Failed { reason: String },
+}
+
+/// Game data for a single asset version, shared by every window using it.
+#[derive(Debug)]
+pub struct LoadedData {
+    pub version: String,
+    pub data: OrbitalData,
+    pub index: DataIndex,
+    pub search: SearchIndex,
+}
+
+#[derive(Clone, Default)]
+pub struct GameData {
+    versions: Arc<Mutex<HashMap<String, Arc<OnceCell<Arc<LoadedData>>>>>>,
+}
+
+impl GameData {
+    pub fn attach<R: Runtime>(handle: &AppHandle<R>) {
+        handle.manage(Self::default());
+    }
+
+    async fn cell(&self, version: &str) -> Arc<OnceCell<Arc<LoadedData>>> {
+        self.versions
+            .lock()
+            .await
+            .entry(version.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Drops the cached data of `version`, so the next use loads it again. Loads already running finish for their
+    /// callers, but aren't cached.
+    pub async fn evict(&self, version: impl AsRef<str>) {
+        self.versions.lock().await.remove(version.as_ref());
+    }
+}
+
+fn load<R: Runtime>(
+    handle: &AppHandle<R>,
+    version: &str,
+    on_progress: &(dyn Fn(DataLoadStage) + Send + Sync),
+) -> crate::Result<LoadedData> {
+    on_progress(DataLoadStage::Reading);
+    let source = handle.asset_source(version)?;
+    let docs_path = source.path().join("docs.json");
+    let raw = source.read("docs.json")?.ok_or(orbital_common::CommonError::from(DocsError::failed_read(
+        docs_path,
+        io::Error::from(io::ErrorKind::NotFound),
+    )))?;
+
+    on_progress(DataLoadStage::Parsing);
+    let generated = serde_json::from_slice::<Generated>(raw.as_slice()).or_else(|e| {
+        Err(orbital_common::CommonError::from(DocsError::invalid_format(format!(
+            "Bad JSON data: {e}"
+        ))))
+    })?;
+    let data = OrbitalData::from(generated);
+
+    on_progress(DataLoadStage::Indexing);
+    let index = DataIndex::build(&data);
+    let search = SearchIndex::build(&data);
+
+    Ok(LoadedData {
+        version: version.to_string(),
+        data,
+        index,
+        search,
+    })
+}
+
+#[async_trait::async_trait]
+pub trait GameDataExt<R: Runtime> {
+    /// Returns the game data for `version`, loading it on first use. Concurrent callers share a single load.
+    async fn game_data(
+        &self,
+        version: String,
+        on_progress: Arc<dyn Fn(DataLoadStage) + Send + Sync>,
+    ) -> crate::Result<Arc<LoadedData>>;
+}
+
+#[async_trait::async_trait]
+impl<R: Runtime, T: Manager<R> + Send + Sync> GameDataExt<R> for T {
+    async fn game_data(
+        &self,
+        version: String,
+        on_progress: Arc<dyn Fn(DataLoadStage) + Send + Sync>,
+    ) -> crate::Result<Arc<LoadedData>> {
+        let cell = self.state::<GameData>().cell(version.as_str()).await;
+        let handle = self.app_handle().clone();
+
+        let loaded = cell
+            .get_or_try_init(|| async move {
+                let progress = on_progress.clone();
+                let result = tokio::task::spawn_blocking(move || load(&handle, version.as_str(), progress.as_ref()))
+                    .await
+                    .or_else(|e| Err(crate::Error::from(tauri::Error::JoinError(e))))?;
+
+                match result {
+                    Ok(data) => {
+                        on_progress(DataLoadStage::Ready);
+                        Ok(Arc::new(data))
+                    }
+                    Err(e) => {
+                        on_progress(DataLoadStage::Failed { reason: e.to_string() });
+                        Err(e)
+                    }
+                }
+            })
+            .await?;
+
+        Ok(loaded.clone())
+    }
+}
@@ -0,0 +1,423 @@
+use bson::doc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+use super::{Project, Record};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct Factory {
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A group of identical machines running a single recipe.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ProductionLine {
+    pub id: String,
+    pub factory_id: String,
+
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Recipe id, as keyed in `OrbitalData::recipes`
+    pub recipe: String,
+
+    /// Building id, as keyed in `OrbitalData::buildables`. Defaults to the recipe's first machine.
+    #[serde(default)]
+    pub machine: Option<String>,
+    pub machine_count: u32,
+
+    /// Clock speed in percent (1 - 250)
+    pub clock_speed: f32,
+
+    /// Production amplification in percent (0 - 100)
+    #[serde(default)]
+    pub amplification: f32,
+
+    /// Set when switching asset versions changed something this line depends on
+    #[serde(default)]
+    pub needs_review: bool,
+}
+
+/// An item flow between two production lines. A missing source or target is an input into/output out of the factory.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct LineLink {
+    pub id: String,
+    pub factory_id: String,
+
+    #[serde(default)]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    pub target: Option<String>,
+
+    /// Item id, as keyed in `OrbitalData::descriptions`
+    pub item: String,
+
+    /// Items per minute
+    pub rate: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct Note {
+    pub id: String,
+
+    #[serde(default)]
+    pub factory_id: Option<String>,
+
+    #[serde(default)]
+    pub line_id: Option<String>,
+    pub title: String,
+    pub content: String,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Record for Factory {
+    const COLLECTION: &'static str = "factories";
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl Record for ProductionLine {
+    const COLLECTION: &'static str = "production_lines";
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl Record for LineLink {
+    const COLLECTION: &'static str = "line_links";
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+impl Record for Note {
+    const COLLECTION: &'static str = "notes";
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct FactoryCreationModel {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct ProductionLineCreationModel {
+    pub factory_id: String,
+
+    #[serde(default)]
+    pub name: Option<String>,
+    pub recipe: String,
+
+    #[serde(default)]
+    pub machine: Option<String>,
+    pub machine_count: u32,
+    pub clock_speed: f32,
+
+    #[serde(default)]
+    pub amplification: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct LineLinkCreationModel {
+    pub factory_id: String,
+
+    #[serde(default)]
+    pub source: Option<String>,
+
+    #[serde(default)]
+    pub target: Option<String>,
+    pub item: String,
+    pub rate: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct NoteCreationModel {
+    #[serde(default)]
+    pub factory_id: Option<String>,
+
+    #[serde(default)]
+    pub line_id: Option<String>,
+    pub title: String,
+    pub content: String,
+}
+
+impl Factory {
+    pub fn new(model: FactoryCreationModel) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: model.name,
+            description: model.description,
+        }
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "Factory name cannot be empty",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ProductionLine {
+    pub fn new(model: ProductionLineCreationModel) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            factory_id: model.factory_id,
+            name: model.name,
+            recipe: model.recipe,
+            machine: model.machine,
+            machine_count: model.machine_count,
+            clock_speed: model.clock_speed,
+            amplification: model.amplification,
+            needs_review: false,
+        }
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.recipe.is_empty() {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "A recipe is required",
+            ));
+        }
+        if self.machine_count == 0 {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "Machine count must be at least 1",
+            ));
+        }
+        if !(1.0..=250.0).contains(&self.clock_speed) {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "Clock speed must be between 1% and 250%",
+            ));
+        }
+        if !(0.0..=100.0).contains(&self.amplification) {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "Amplification must be between 0% and 100%",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl LineLink {
+    pub fn new(model: LineLinkCreationModel) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            factory_id: model.factory_id,
+            source: model.source,
+            target: model.target,
+            item: model.item,
+            rate: model.rate,
+        }
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.source.is_none() && self.target.is_none() {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "A link needs at least a source or a target line",
+            ));
+        }
+        if self.source.is_some() && self.source == self.target {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "A line cannot be linked to itself",
+            ));
+        }
+        if !self.rate.is_finite() || self.rate < 0.0 {
+            return Err(crate::ProjectError::invalid_record(
+                Self::COLLECTION,
+                "Rate must be a non-negative number",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Note {
+    pub fn new(model: NoteCreationModel) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            factory_id: model.factory_id,
+            line_id: model.line_id,
+            title: model.title,
+            content: model.content,
+            created: now,
+            updated: now,
+        }
+    }
+}
+
+impl Project {
+    /// Removes a factory along with its production lines, links and notes.
+    pub async fn remove_factory(&self, id: impl AsRef<str>) -> crate::Result<Factory> {
+        let factory = self.get_record::<Factory>(id.as_ref()).await?;
+        for line in self
+            .find_records::<ProductionLine>(doc! {"factory_id": id.as_ref()})
+            .await?
+        {
+            self.remove_line(line.id).await?;
+        }
+        for link in self
+            .find_records::<LineLink>(doc! {"factory_id": id.as_ref()})
+            .await?
+        {
+            self.remove_record::<LineLink>(link.id).await?;
+        }
+        for note in self
+            .find_records::<Note>(doc! {"factory_id": id.as_ref()})
+            .await?
+        {
+            self.remove_record::<Note>(note.id).await?;
+        }
+
+        self.remove_record::<Factory>(factory.id).await
+    }
+
+    /// Removes a production line along with the links and notes attached to it.
+    pub async fn remove_line(&self, id: impl AsRef<str>) -> crate::Result<ProductionLine> {
+        let line = self.get_record::<ProductionLine>(id.as_ref()).await?;
+        for link in self
+            .find_records::<LineLink>(doc! {"factory_id": line.factory_id.clone()})
+            .await?
+        {
+            if link.source.as_deref() == Some(id.as_ref()) || link.target.as_deref() == Some(id.as_ref()) {
+                self.remove_record::<LineLink>(link.id).await?;
+            }
+        }
+        for note in self
+            .find_records::<Note>(doc! {"line_id": id.as_ref()})
+            .await?
+        {
+            self.remove_record::<Note>(note.id).await?;
+        }
+
+        self.remove_record::<ProductionLine>(line.id).await
+    }
+
+    /// Checks that the factory and line a note is attached to exist, and that the line belongs to that factory.
+    pub async fn check_note(&self, note: &Note) -> crate::Result<()> {
+        if let Some(factory_id) = note.factory_id.clone() {
+            let _ = self.get_record::<Factory>(factory_id).await?;
+        }
+        if let Some(line_id) = note.line_id.clone() {
+            let line = self.get_record::<ProductionLine>(line_id).await?;
+            if note.factory_id.as_ref().is_some_and(|factory_id| factory_id != &line.factory_id) {
+                return Err(crate::ProjectError::invalid_record(
+                    Note::COLLECTION,
+                    "A note's line must belong to its factory",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the lines referenced by a link exist and belong to the link's factory.
+    pub async fn check_link(&self, link: &LineLink) -> crate::Result<()> {
+        let _ = self.get_record::<Factory>(link.factory_id.clone()).await?;
+        for line_id in [link.source.clone(), link.target.clone()].into_iter().flatten() {
+            let line = self.get_record::<ProductionLine>(line_id).await?;
+            if line.factory_id != link.factory_id {
+                return Err(crate::ProjectError::invalid_record(
+                    LineLink::COLLECTION,
+                    "Linked lines must belong to the same factory",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line() -> ProductionLine {
+        ProductionLine::new(ProductionLineCreationModel {
+            factory_id: String::from("factory"),
+            name: None,
+            recipe: String::from("Recipe_IngotIron_C"),
+            machine: None,
+            machine_count: 2,
+            clock_speed: 100.0,
+            amplification: 0.0,
+        })
+    }
+
+    fn link() -> LineLink {
+        LineLink::new(LineLinkCreationModel {
+            factory_id: String::from("factory"),
+            source: Some(String::from("smelter")),
+            target: Some(String::from("constructor")),
+            item: String::from("Desc_IronIngot_C"),
+            rate: 30.0,
+        })
+    }
+
+    #[test]
+    fn validate_factory() {
+        let factory = Factory::new(FactoryCreationModel {
+            name: String::from("Smelting"),
+            description: String::new(),
+        });
+        assert!(factory.validate().is_ok());
+        assert!(Factory { name: String::from("  "), ..factory }.validate().is_err());
+    }
+
+    #[test]
+    fn validate_line() {
+        assert!(line().validate().is_ok());
+        assert!(ProductionLine { clock_speed: 250.0, amplification: 100.0, ..line() }.validate().is_ok());
+
+        assert!(ProductionLine { recipe: String::new(), ..line() }.validate().is_err());
+        assert!(ProductionLine { machine_count: 0, ..line() }.validate().is_err());
+        assert!(ProductionLine { clock_speed: 0.5, ..line() }.validate().is_err());
+        assert!(ProductionLine { clock_speed: 250.5, ..line() }.validate().is_err());
+        assert!(ProductionLine { clock_speed: f32::NAN, ..line() }.validate().is_err());
+        assert!(ProductionLine { amplification: -1.0, ..line() }.validate().is_err());
+        assert!(ProductionLine { amplification: f32::NAN, ..line() }.validate().is_err());
+    }
+
+    #[test]
+    fn validate_link() {
+        assert!(link().validate().is_ok());
+        assert!(LineLink { source: None, rate: 0.0, ..link() }.validate().is_ok());
+        assert!(LineLink { target: None, ..link() }.validate().is_ok());
+
+        assert!(LineLink { source: None, target: None, ..link() }.validate().is_err());
+        assert!(LineLink { target: Some(String::from("smelter")), ..link() }.validate().is_err());
+        assert!(LineLink { rate: -1.0, ..link() }.validate().is_err());
+        assert!(LineLink { rate: f32::NAN, ..link() }.validate().is_err());
+        assert!(LineLink { rate: f32::INFINITY, ..link() }.validate().is_err());
+    }
+}
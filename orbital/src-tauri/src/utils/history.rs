@@ -0,0 +1,275 @@
+use std::{cell::RefCell, future::Future, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use uuid::Uuid;
+
+use super::{AppStateExt, JournalOp, Project, Record};
+
+/// State of a single record before and after a command touched it. `None` means the record did not exist.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct RecordChange {
+    pub collection: String,
+    pub id: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl RecordChange {
+    pub fn of<T: Record>(id: impl AsRef<str>, before: Option<&T>, after: Option<&T>) -> crate::Result<Self> {
+        let to_value = |record: Option<&T>| {
+            record
+                .map(serde_json::to_value)
+                .transpose()
+                .or_else(|e| Err(crate::Error::serialization(e)))
+        };
+
+        Ok(Self {
+            collection: T::COLLECTION.to_string(),
+            id: id.as_ref().to_string(),
+            before: to_value(before)?,
+            after: to_value(after)?,
+        })
+    }
+
+    /// The edit that puts the record back into the given state.
+    fn restore(&self, state: &Option<serde_json::Value>) -> JournalOp {
+        match state {
+            Some(record) => JournalOp::Upsert {
+                collection: self.collection.clone(),
+                id: self.id.clone(),
+                record: record.clone(),
+            },
+            None => JournalOp::Remove {
+                collection: self.collection.clone(),
+                id: self.id.clone(),
+            },
+        }
+    }
+}
+
+/// One undoable command, stored in the `history` collection of `project.db`.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub sequence: u32,
+    pub label: String,
+    pub timestamp: DateTime<Utc>,
+    pub undone: bool,
+    pub changes: Vec<RecordChange>,
+}
+
+impl Record for HistoryEntry {
+    const COLLECTION: &'static str = "history";
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+}
+
+/// A history entry without its record snapshots, as listed to the frontend.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryItem {
+    pub id: String,
+    pub sequence: u32,
+    pub label: String,
+    pub timestamp: DateTime<Utc>,
+    pub undone: bool,
+    pub changes: u32,
+}
+
+impl From<&HistoryEntry> for HistoryItem {
+    fn from(value: &HistoryEntry) -> Self {
+        Self {
+            id: value.id.clone(),
+            sequence: value.sequence,
+            label: value.label.clone(),
+            timestamp: value.timestamp,
+            undone: value.undone,
+            changes: value.changes.len() as u32,
+        }
+    }
+}
+
+/// Changes made by the command running on the current task.
+struct Recording {
+    /// `HistoryRecorder::command` of the project the command runs on. Writes to other projects aren't recorded.
+    owner: Arc<tokio::sync::Mutex<()>>,
+    changes: RefCell<Vec<RecordChange>>,
+}
+
+tokio::task_local! {
+    static RECORDING: Recording;
+}
+
+/// Serializes the commands of a project and records their changes. Shared between clones of a `Project`.
+#[derive(Clone, Default)]
+pub struct HistoryRecorder {
+    command: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl HistoryRecorder {
+    /// Adds a change to the command running on the current task. Changes made outside of a command, including
+    /// those made by other tasks while a command runs, are not recorded.
+    pub fn push(&self, change: RecordChange) {
+        let _ = RECORDING.try_with(|recording| {
+            if Arc::ptr_eq(&recording.owner, &self.command) {
+                recording.changes.borrow_mut().push(change);
+            }
+        });
+    }
+
+    /// Waits for the running command to finish and keeps new ones from starting until the guard is dropped.
+    pub(crate) async fn pause(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.command.lock().await
+    }
+}
+
+impl Project {
+    /// Runs `action` as a single undoable command labelled `label`. Commands are serialized, and every record
+    /// change `action` makes belongs to this command. If `action` fails, its changes are reverted and nothing is
+    /// added to the history.
+    pub async fn command<T>(&self, label: impl AsRef<str>, action: impl Future<Output = crate::Result<T>>) -> crate::Result<T> {
+        let _running = self.history().command.lock().await;
+        let recording = Recording {
+            owner: self.history().command.clone(),
+            changes: RefCell::new(Vec::new()),
+        };
+        let (result, changes) = RECORDING
+            .scope(recording, async {
+                let result = action.await;
+                (result, RECORDING.with(|recording| recording.changes.take()))
+            })
+            .await;
+
+        if let Err(e) = result {
+            for change in changes.iter().rev() {
+                if let Err(revert) = self.apply_op(&change.restore(&change.before)).await {
+                    log::error!("Failed to revert {}/{} after a failed command: {revert}", change.collection, change.id);
+                }
+            }
+            return Err(e);
+        }
+        if !changes.is_empty() {
+            self.push_history(label.as_ref(), changes).await?;
+        }
+        result
+    }
+
+    async fn history_entries(&self) -> crate::Result<Vec<HistoryEntry>> {
+        let mut entries = self.records::<HistoryEntry>().await?;
+        entries.sort_by_key(|entry| entry.sequence);
+        Ok(entries)
+    }
+
+    async fn push_history(&self, label: &str, changes: Vec<RecordChange>) -> crate::Result<()> {
+        let depth = self.handle().app_state().await.history_depth as usize;
+        let entries = self.history_entries().await?;
+
+        // A new command discards everything that could have been redone.
+        for entry in entries.iter().filter(|entry| entry.undone) {
+            self.remove_record::<HistoryEntry>(entry.id.clone()).await?;
+        }
+        if depth == 0 {
+            return Ok(());
+        }
+
+        let applied: Vec<&HistoryEntry> = entries.iter().filter(|entry| !entry.undone).collect();
+        self.insert_record(HistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            sequence: entries.last().map_or(0, |entry| entry.sequence + 1),
+            label: label.to_string(),
+            timestamp: Utc::now(),
+            undone: false,
+            changes,
+        })
+        .await?;
+
+        for entry in applied.iter().take((applied.len() + 1).saturating_sub(depth)) {
+            self.remove_record::<HistoryEntry>(entry.id.clone()).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn history_items(&self) -> crate::Result<Vec<HistoryItem>> {
+        Ok(self.history_entries().await?.iter().map(HistoryItem::from).collect())
+    }
+
+    /// Reverts the most recent command that has not been undone yet.
+    pub async fn undo(&self) -> crate::Result<Option<HistoryItem>> {
+        let _running = self.history().command.lock().await;
+        let Some(mut entry) = self.history_entries().await?.into_iter().rev().find(|entry| !entry.undone) else {
+            return Ok(None);
+        };
+
+        for change in entry.changes.iter().rev() {
+            self.apply_op(&change.restore(&change.before)).await?;
+        }
+        entry.undone = true;
+        let entry = self.save_record(entry).await?;
+        Ok(Some(HistoryItem::from(&entry)))
+    }
+
+    /// Re-applies the oldest undone command.
+    pub async fn redo(&self) -> crate::Result<Option<HistoryItem>> {
+        let _running = self.history().command.lock().await;
+        let Some(mut entry) = self.history_entries().await?.into_iter().find(|entry| entry.undone) else {
+            return Ok(None);
+        };
+
+        for change in entry.changes.iter() {
+            self.apply_op(&change.restore(&change.after)).await?;
+        }
+        entry.undone = false;
+        let entry = self.save_record(entry).await?;
+        Ok(Some(HistoryItem::from(&entry)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(id: &str) -> RecordChange {
+        RecordChange {
+            collection: "factories".to_string(),
+            id: id.to_string(),
+            before: None,
+            after: Some(serde_json::json!({ "id": id })),
+        }
+    }
+
+    async fn recorded(recorder: &HistoryRecorder, action: impl Future<Output = ()>) -> Vec<String> {
+        let recording = Recording {
+            owner: recorder.command.clone(),
+            changes: RefCell::new(Vec::new()),
+        };
+        RECORDING
+            .scope(recording, async {
+                action.await;
+                RECORDING.with(|recording| recording.changes.take())
+            })
+            .await
+            .into_iter()
+            .map(|change| change.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn record_only_the_running_command() {
+        let recorder = HistoryRecorder::default();
+        let other = HistoryRecorder::default();
+        recorder.push(change("outside"));
+
+        let concurrent = recorder.clone();
+        let ids = recorded(&recorder, async {
+            recorder.clone().push(change("own"));
+            other.push(change("other project"));
+            tokio::spawn(async move { concurrent.push(change("other task")) }).await.unwrap();
+        })
+        .await;
+
+        assert_eq!(ids, vec!["own".to_string()]);
+    }
+}
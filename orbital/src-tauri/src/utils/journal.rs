@@ -0,0 +1,408 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::{Project, Record};
+
+/// Write-ahead log of record edits, appended before each edit reaches `project.db`.
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Journal left behind by a session that did not close cleanly, waiting to be replayed or discarded.
+pub const PENDING_JOURNAL_FILE: &str = "journal.pending.jsonl";
+
+/// Marker that exists for as long as a project is open. Finding it on open means the last session crashed.
+pub const SESSION_FILE: &str = ".session";
+
+/// Entries written since the last compaction before the journal is compacted again.
+const COMPACT_AFTER: usize = 256;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalOp {
+    Upsert {
+        collection: String,
+        id: String,
+        record: serde_json::Value,
+    },
+    Remove {
+        collection: String,
+        id: String,
+    },
+}
+
+impl JournalOp {
+    pub fn upsert<T: Record>(record: &T) -> crate::Result<Self> {
+        Ok(Self::Upsert {
+            collection: T::COLLECTION.to_string(),
+            id: record.id(),
+            record: serde_json::to_value(record).or_else(|e| Err(crate::Error::serialization(e)))?,
+        })
+    }
+
+    pub fn remove<T: Record>(id: impl AsRef<str>) -> Self {
+        Self::Remove {
+            collection: T::COLLECTION.to_string(),
+            id: id.as_ref().to_string(),
+        }
+    }
+
+    fn key(&self) -> (String, String) {
+        match self {
+            Self::Upsert { collection, id, .. } | Self::Remove { collection, id } => (collection.clone(), id.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+
+    #[serde(flatten)]
+    pub op: JournalOp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SessionMarker {
+    pid: u32,
+    started: DateTime<Utc>,
+}
+
+/// Summary of a journal recovered from an unclean shutdown.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct RecoveryInfo {
+    pub project: String,
+    pub entries: u32,
+    pub first_edit: Option<DateTime<Utc>>,
+    pub last_edit: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Journal {
+    directory: PathBuf,
+    written: Arc<Mutex<usize>>,
+    attached: bool,
+
+    /// When this session last wrote to the project, so the projects watcher can tell its own writes apart.
+    last_write: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Journal {
+    /// Starts a journaling session in `directory`. A journal left over from a crashed session is moved
+    /// aside to `PENDING_JOURNAL_FILE` (merging with any earlier pending journal) so new edits start clean.
+    pub fn begin(directory: impl AsRef<Path>) -> crate::Result<Self> {
+        let journal = Self {
+            directory: directory.as_ref().to_path_buf(),
+            written: Arc::new(Mutex::new(0)),
+            attached: true,
+            last_write: Arc::new(Mutex::new(None)),
+        };
+
+        let journal_path = journal.path(JOURNAL_FILE);
+        if journal_path.exists() {
+            if journal.path(SESSION_FILE).exists() {
+                let mut entries = read_entries(journal.path(PENDING_JOURNAL_FILE))?;
+                entries.extend(read_entries(&journal_path)?);
+                write_entries(journal.path(PENDING_JOURNAL_FILE), &compact(entries))?;
+            }
+            fs::remove_file(&journal_path)?;
+        }
+
+        let marker = SessionMarker {
+            pid: std::process::id(),
+            started: Utc::now(),
+        };
+        fs::write(
+            journal.path(SESSION_FILE),
+            serde_json::to_vec(&marker).or_else(|e| Err(crate::Error::serialization(e)))?,
+        )?;
+
+        Ok(journal)
+    }
+
+    /// A journal for a project opened read-only. It never touches the files of the session that owns the project.
+    pub fn detached(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            written: Arc::new(Mutex::new(0)),
+            attached: false,
+            last_write: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(name)
+    }
+
+    /// Appends an edit to the journal and syncs it to disk. Compacts the journal every `COMPACT_AFTER` entries.
+    pub fn record(&self, op: JournalOp) -> crate::Result<()> {
+        let mut written = self.written.lock().unwrap();
+        self.touch();
+        let entry = JournalEntry {
+            timestamp: Utc::now(),
+            op,
+        };
+        let mut line = serde_json::to_vec(&entry).or_else(|e| Err(crate::Error::serialization(e)))?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(JOURNAL_FILE))?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+
+        *written += 1;
+        if *written >= COMPACT_AFTER {
+            let path = self.path(JOURNAL_FILE);
+            write_entries(&path, &compact(read_entries(&path)?))?;
+            *written = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Marks the project as just written by this session.
+    pub fn touch(&self) {
+        *self.last_write.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Whether this session wrote to the project within the last `window`.
+    pub fn wrote_within(&self, window: Duration) -> bool {
+        self.last_write.lock().unwrap().is_some_and(|written| written.elapsed() < window)
+    }
+
+    /// Ends the session after the database has been flushed, dropping the journal and the session marker.
+    pub fn finish(&self) -> crate::Result<()> {
+        if !self.attached {
+            return Ok(());
+        }
+
+        let _guard = self.written.lock().unwrap();
+        for name in [JOURNAL_FILE, SESSION_FILE] {
+            let path = self.path(name);
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn pending(&self) -> crate::Result<Vec<JournalEntry>> {
+        read_entries(self.path(PENDING_JOURNAL_FILE))
+    }
+
+    pub fn discard_pending(&self) -> crate::Result<()> {
+        let path = self.path(PENDING_JOURNAL_FILE);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps only the latest entry for each record, preserving the order of those entries.
+fn compact(entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+    let mut latest: HashMap<(String, String), usize> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        latest.insert(entry.op.key(), index);
+    }
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|(index, entry)| latest.get(&entry.op.key()) == Some(index))
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Reads every complete entry of a journal file. A torn last line (from a crash mid-write) is ignored.
+fn read_entries(path: impl AsRef<Path>) -> crate::Result<Vec<JournalEntry>> {
+    if !path.as_ref().exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<JournalEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => break,
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Replaces a journal file atomically with the given entries.
+fn write_entries(path: impl AsRef<Path>, entries: &[JournalEntry]) -> crate::Result<()> {
+    let path = path.as_ref();
+    let partial = path.with_extension("jsonl.partial");
+    {
+        let mut file = File::create(&partial)?;
+        for entry in entries {
+            let mut line = serde_json::to_vec(entry).or_else(|e| Err(crate::Error::serialization(e)))?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+        }
+        file.sync_all()?;
+    }
+
+    fs::rename(partial, path)?;
+    Ok(())
+}
+
+impl Project {
+    /// Describes the journal left behind by a crashed session, if there is one. Read-only projects never recover.
+    pub fn pending_recovery(&self) -> crate::Result<Option<RecoveryInfo>> {
+        if self.is_read_only() {
+            return Ok(None);
+        }
+
+        let entries = self.journal().pending()?;
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(RecoveryInfo {
+            project: self.id(),
+            entries: entries.len() as u32,
+            first_edit: entries.first().map(|entry| entry.timestamp),
+            last_edit: entries.last().map(|entry| entry.timestamp),
+        }))
+    }
+
+    /// Replays the pending journal into `project.db`, then discards it. Returns the number of entries applied.
+    pub async fn replay_journal(&self) -> crate::Result<u32> {
+        self.ensure_writable()?;
+        let entries = self.journal().pending()?;
+        for entry in entries.iter() {
+            self.apply_op(&entry.op).await?;
+        }
+
+        self.journal().discard_pending()?;
+        Ok(entries.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upsert(id: &str, value: u32) -> JournalOp {
+        JournalOp::Upsert {
+            collection: "factories".to_string(),
+            id: id.to_string(),
+            record: serde_json::json!({ "id": id, "value": value }),
+        }
+    }
+
+    fn remove(id: &str) -> JournalOp {
+        JournalOp::Remove {
+            collection: "factories".to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    fn entry(op: JournalOp) -> JournalEntry {
+        JournalEntry { timestamp: Utc::now(), op }
+    }
+
+    fn ids(entries: &[JournalEntry]) -> Vec<(String, Option<u64>)> {
+        entries
+            .iter()
+            .map(|entry| match &entry.op {
+                JournalOp::Upsert { id, record, .. } => (id.clone(), record["value"].as_u64()),
+                JournalOp::Remove { id, .. } => (id.clone(), None),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn read_entries_skips_torn_line() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(JOURNAL_FILE);
+        write_entries(&path, &[entry(upsert("a", 1)), entry(remove("b"))]).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\n{\"timestamp\":\"2025-01-01T00:00:00Z\",\"op\":\"ups").unwrap();
+
+        assert_eq!(ids(&read_entries(&path).unwrap()), vec![("a".to_string(), Some(1)), ("b".to_string(), None)]);
+        assert!(read_entries(directory.path().join(PENDING_JOURNAL_FILE)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn compact_keeps_latest_entry_per_record() {
+        let entries = vec![
+            entry(upsert("a", 1)),
+            entry(upsert("b", 1)),
+            entry(upsert("a", 2)),
+            entry(remove("b")),
+            entry(upsert("c", 1)),
+        ];
+
+        assert_eq!(
+            ids(&compact(entries)),
+            vec![("a".to_string(), Some(2)), ("b".to_string(), None), ("c".to_string(), Some(1))]
+        );
+    }
+
+    #[test]
+    fn record_compacts_journal() {
+        let directory = tempfile::tempdir().unwrap();
+        let journal = Journal::begin(directory.path()).unwrap();
+        for value in 0..COMPACT_AFTER as u32 {
+            journal.record(upsert("a", value)).unwrap();
+        }
+        journal.record(upsert("b", 0)).unwrap();
+
+        let entries = read_entries(directory.path().join(JOURNAL_FILE)).unwrap();
+        assert_eq!(ids(&entries), vec![("a".to_string(), Some(COMPACT_AFTER as u64 - 1)), ("b".to_string(), Some(0))]);
+    }
+
+    #[test]
+    fn crashed_session_becomes_pending() {
+        let directory = tempfile::tempdir().unwrap();
+        let crashed = Journal::begin(directory.path()).unwrap();
+        crashed.record(upsert("a", 1)).unwrap();
+
+        let second = Journal::begin(directory.path()).unwrap();
+        assert!(!directory.path().join(JOURNAL_FILE).exists());
+        second.record(upsert("a", 2)).unwrap();
+        second.record(upsert("b", 1)).unwrap();
+
+        let third = Journal::begin(directory.path()).unwrap();
+        assert_eq!(ids(&third.pending().unwrap()), vec![("a".to_string(), Some(2)), ("b".to_string(), Some(1))]);
+
+        third.finish().unwrap();
+        assert!(!directory.path().join(SESSION_FILE).exists());
+        let clean = Journal::begin(directory.path()).unwrap();
+        assert_eq!(clean.pending().unwrap().len(), 2);
+        clean.discard_pending().unwrap();
+        assert!(clean.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn detached_journal_leaves_session_alone() {
+        let directory = tempfile::tempdir().unwrap();
+        let owner = Journal::begin(directory.path()).unwrap();
+        owner.record(upsert("a", 1)).unwrap();
+
+        Journal::detached(directory.path()).finish().unwrap();
+        assert!(directory.path().join(SESSION_FILE).exists());
+        assert_eq!(read_entries(directory.path().join(JOURNAL_FILE)).unwrap().len(), 1);
+    }
+}
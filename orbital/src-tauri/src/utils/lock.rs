@@ -0,0 +1,219 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// Advisory lock held by the Orbital instance that has a project open for writing.
+pub const LOCK_FILE: &str = "project.lock";
+
+/// How long a lock file that can't be parsed counts as held. Another instance may have just created it and not
+/// finished writing it yet; an older one was left behind by a crash while it was written.
+const UNREADABLE_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectLock {
+    pub pid: u32,
+    pub host: String,
+    pub acquired: DateTime<Utc>,
+}
+
+fn current_host() -> String {
+    System::host_name().unwrap_or_else(|| String::from("unknown"))
+}
+
+impl ProjectLock {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: current_host(),
+            acquired: Utc::now(),
+        }
+    }
+
+    /// Stands in for the holder of a lock file that exists but can't be parsed.
+    fn unknown() -> Self {
+        Self {
+            pid: 0,
+            host: String::from("unknown"),
+            acquired: Utc::now(),
+        }
+    }
+
+    fn path(directory: impl AsRef<Path>) -> PathBuf {
+        directory.as_ref().join(LOCK_FILE)
+    }
+
+    /// Reads the lock of a project directory. Lock files that are missing or can't be parsed give `None`.
+    pub fn read(directory: impl AsRef<Path>) -> Option<Self> {
+        let data = fs::read(Self::path(directory)).ok()?;
+        serde_json::from_slice::<Self>(data.as_slice()).ok()
+    }
+
+    /// How long ago the lock file of `directory` was last written, or `None` if there is none.
+    fn age(directory: impl AsRef<Path>) -> Option<Duration> {
+        let modified = fs::metadata(Self::path(directory)).ok()?.modified().ok()?;
+        Some(modified.elapsed().unwrap_or_default())
+    }
+
+    /// Whether `directory` has a lock file that can't be parsed and was written too recently to be abandoned.
+    fn is_unreadable_held(directory: impl AsRef<Path>) -> bool {
+        Self::age(directory).is_some_and(|age| age < UNREADABLE_GRACE)
+    }
+
+    /// A lock is stale when it was taken on this host by a process that no longer runs. Locks from other hosts
+    /// cannot be checked and are never considered stale.
+    pub fn is_stale(&self) -> bool {
+        if self.host != current_host() {
+            return false;
+        }
+
+        let pid = Pid::from_u32(self.pid);
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        system.process(pid).is_none()
+    }
+
+    /// Takes the lock of the project `id` in `directory`, removing a stale lock first. A lock file that can't be
+    /// parsed is treated as held for `UNREADABLE_GRACE` after it was written, and as stale afterwards.
+    pub fn acquire(id: impl AsRef<str>, directory: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = Self::path(&directory);
+        let lock = Self::current();
+        let data = serde_json::to_vec_pretty(&lock).or_else(|e| Err(crate::Error::serialization(e)))?;
+
+        let mut removed_stale = false;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(&data)?;
+                    file.sync_all()?;
+                    return Ok(lock);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => match Self::read(&directory) {
+                    Some(existing) if existing.is_stale() && !removed_stale => {
+                        log::warn!("Removing stale lock of project {}", id.as_ref());
+                        fs::remove_file(&path)?;
+                        removed_stale = true;
+                    }
+                    Some(existing) => return Err(crate::ProjectError::locked(id, &existing)),
+                    None if removed_stale || Self::is_unreadable_held(&directory) => {
+                        return Err(crate::ProjectError::locked(id, &Self::unknown()));
+                    }
+                    None => {
+                        log::warn!("Removing abandoned lock of project {}", id.as_ref());
+                        match fs::remove_file(&path) {
+                            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                            _ => removed_stale = true,
+                        }
+                    }
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Fails with `ProjectError::Locked` if another live process holds the lock of `directory`, or if its lock file
+    /// can't be parsed and was written within `UNREADABLE_GRACE`.
+    pub fn check(id: impl AsRef<str>, directory: impl AsRef<Path>) -> crate::Result<()> {
+        match Self::read(&directory) {
+            Some(existing) if !existing.is_stale() => Err(crate::ProjectError::locked(id, &existing)),
+            None if Self::is_unreadable_held(&directory) => Err(crate::ProjectError::locked(id, &Self::unknown())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes the lock file, if it is still this lock.
+    pub fn release(&self, directory: impl AsRef<Path>) -> crate::Result<()> {
+        if Self::read(&directory).is_some_and(|existing| &existing == self) {
+            fs::remove_file(Self::path(directory))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A lock taken on this host by a process that can't be running.
+    fn stale() -> ProjectLock {
+        ProjectLock {
+            pid: u32::MAX - 1,
+            host: current_host(),
+            acquired: Utc::now(),
+        }
+    }
+
+    fn write(directory: &Path, lock: &ProjectLock) {
+        fs::write(directory.join(LOCK_FILE), serde_json::to_vec(lock).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn staleness() {
+        assert!(!ProjectLock::current().is_stale());
+        assert!(stale().is_stale());
+        assert!(!ProjectLock { host: format!("{}-elsewhere", current_host()), ..stale() }.is_stale());
+    }
+
+    #[test]
+    fn acquire_and_release() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock = ProjectLock::acquire("a", directory.path()).unwrap();
+        assert_eq!(ProjectLock::read(directory.path()), Some(lock.clone()));
+        assert!(ProjectLock::acquire("a", directory.path()).is_err());
+        assert!(ProjectLock::check("a", directory.path()).is_err());
+
+        lock.release(directory.path()).unwrap();
+        assert!(!directory.path().join(LOCK_FILE).exists());
+        assert!(ProjectLock::check("a", directory.path()).is_ok());
+    }
+
+    #[test]
+    fn release_keeps_other_locks() {
+        let directory = tempfile::tempdir().unwrap();
+        let other = ProjectLock { host: format!("{}-elsewhere", current_host()), ..stale() };
+        write(directory.path(), &other);
+
+        ProjectLock::current().release(directory.path()).unwrap();
+        assert_eq!(ProjectLock::read(directory.path()), Some(other));
+    }
+
+    #[test]
+    fn acquire_replaces_stale_lock() {
+        let directory = tempfile::tempdir().unwrap();
+        write(directory.path(), &stale());
+        assert!(ProjectLock::check("a", directory.path()).is_ok());
+
+        let lock = ProjectLock::acquire("a", directory.path()).unwrap();
+        assert_eq!(ProjectLock::read(directory.path()), Some(lock));
+    }
+
+    #[test]
+    fn unreadable_lock_is_held() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(directory.path().join(LOCK_FILE), b"{\"pid\": 12").unwrap();
+
+        assert!(ProjectLock::acquire("a", directory.path()).is_err());
+        assert!(ProjectLock::check("a", directory.path()).is_err());
+        assert_eq!(fs::read(directory.path().join(LOCK_FILE)).unwrap(), b"{\"pid\": 12");
+    }
+
+    #[test]
+    fn abandoned_unreadable_lock_is_replaced() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(LOCK_FILE);
+        let file = fs::File::create(&path).unwrap();
+        file.set_modified(std::time::SystemTime::now() - UNREADABLE_GRACE * 2).unwrap();
+        drop(file);
+        assert!(ProjectLock::check("a", directory.path()).is_ok());
+
+        let lock = ProjectLock::acquire("a", directory.path()).unwrap();
+        assert_eq!(ProjectLock::read(directory.path()), Some(lock));
+    }
+}
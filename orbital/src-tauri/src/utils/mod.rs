@@ -1,5 +1,38 @@
 pub mod app_state;
+pub mod archive;
+pub mod asset_migration;
+pub mod assets;
+pub mod backups;
+pub mod data;
+pub mod factories;
+pub mod history;
+pub mod journal;
+pub mod lock;
 pub mod projects;
+pub mod protocol;
+pub mod records;
+pub mod templates;
+pub mod text_storage;
+pub mod trash;
+pub mod watcher;
 
-pub use app_state::{AppState, AppStateExt, StatePatch};
+pub use app_state::{AppState, AppStateExt, AppStatePatcher, StatePatch, UnitsPreference};
+pub use archive::{ArchiveExt, ArchiveManifest, ImportWarning, ProjectImport};
+pub use asset_migration::AssetMigrationReport;
+pub use assets::{AssetInstallReport, AssetSource, AssetVersionReference, AssetsExt};
+pub use backups::{Backup, BackupsExt, SnapshotReason};
+pub use data::{DataLoadStage, GameData, GameDataExt, LoadedData};
+pub use factories::{
+    Factory, FactoryCreationModel, LineLink, LineLinkCreationModel, Note, NoteCreationModel,
+    ProductionLine, ProductionLineCreationModel,
+};
+pub use history::{HistoryEntry, HistoryItem, HistoryRecorder, RecordChange};
+pub use journal::{Journal, JournalEntry, JournalOp, RecoveryInfo};
+pub use lock::ProjectLock;
 pub use projects::{Project, ProjectConfig, ProjectsExt};
+pub use protocol::PackArchives;
+pub use records::{Record, COLLECTIONS};
+pub use templates::{ProjectTemplate, TemplateInfo, TemplateSource, TemplatesExt};
+pub use text_storage::{discard_storage, StorageFormat, TextStorage};
+pub use trash::{TrashEntry, TrashExt};
+pub use watcher::{ProjectsChange, ProjectsWatcher};
@@ -1,6 +1,8 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use convert_case::{Case, Casing};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_slice, to_string_pretty};
 use specta::Type;
@@ -9,14 +11,28 @@ use tauri_plugin_persistence::{
     types::ContextSpecifier, Context, Database, FileHandleMode, PersistenceExt,
 };
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use super::{
+    backups::{rotate, snapshot, SnapshotReason},
+    AppStateExt, HistoryRecorder, Journal, ProjectLock, StorageFormat, TextStorage, TrashExt,
+};
 
-use super::AppStateExt;
+/// Schema version written by this build of Orbital. Bump alongside a new arm in `migrate_step`.
+pub const SCHEMA_VERSION: u32 = 1;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
 pub struct ProjectConfig {
     pub id: String,
     pub name: String,
     pub asset_version: String,
+
+    #[serde(default)]
+    pub schema_version: u32,
+
+    /// Where the project keeps its records. Projects created before text storage existed use `project.db`.
+    #[serde(default)]
+    pub storage: StorageFormat,
 }
 
 impl ProjectConfig {
@@ -24,17 +40,32 @@ impl ProjectConfig {
         let name = name.as_ref().to_string();
         let asset_version = asset_version.as_ref().to_string();
         Self {
-            id: name.to_case(Case::Snake),
+            id: Uuid::new_v4().to_string(),
             name,
             asset_version,
+            schema_version: SCHEMA_VERSION,
+            storage: StorageFormat::default(),
         }
     }
+
+    /// Projects created before ids were UUIDs used the snake-cased name as both id and folder name.
+    pub fn is_legacy(&self) -> bool {
+        Uuid::parse_str(&self.id).is_err()
+    }
 }
 
 pub struct Project {
     context: Context<Wry>,
     config: ProjectConfig,
     handle: AppHandle<Wry>,
+    journal: Journal,
+
+    /// Folder the project was opened from. Fixed for the session, even if `AppState::project_path` changes.
+    directory: PathBuf,
+    history: HistoryRecorder,
+
+    /// Lock held by this project, or `None` if it was opened read-only.
+    lock: Option<ProjectLock>,
 }
 
 impl Clone for Project {
@@ -43,17 +74,17 @@ impl Clone for Project {
             context: self.context.clone(),
             config: self.config.clone(),
             handle: self.handle.clone(),
+            journal: self.journal.clone(),
+            directory: self.directory.clone(),
+            history: self.history.clone(),
+            lock: self.lock.clone(),
         }
     }
 }
 
 impl Project {
-    pub async fn projects_directory(&self) -> PathBuf {
-        self.handle.app_state().await.project_path
-    }
-
-    pub async fn directory(&self) -> PathBuf {
-        self.projects_directory().await.join(self.config.id.clone())
+    pub fn directory(&self) -> PathBuf {
+        self.directory.clone()
     }
 
     pub fn config(&self) -> ProjectConfig {
@@ -61,7 +92,9 @@ impl Project {
     }
 
     pub async fn update_config(&mut self, conf: ProjectConfig) -> crate::Result<ProjectConfig> {
+        self.ensure_writable()?;
         self.config = conf.clone();
+        self.journal.touch();
 
         let conf_handle = self
             .context
@@ -88,13 +121,42 @@ impl Project {
     }
 
     pub async fn close(self) -> crate::Result<()> {
+        let directory = self.directory();
         self.context
             .close()
             .await
             .or_else(|e| Err(crate::Error::from(e)))?;
+        self.journal.finish()?;
+        if let Some(lock) = self.lock.as_ref() {
+            lock.release(directory)?;
+        }
         Ok(())
     }
 
+    pub fn is_read_only(&self) -> bool {
+        self.lock.is_none()
+    }
+
+    pub fn ensure_writable(&self) -> crate::Result<()> {
+        if self.is_read_only() {
+            return Err(crate::ProjectError::read_only(self.id()));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    pub(crate) fn history(&self) -> &HistoryRecorder {
+        &self.history
+    }
+
+    pub(crate) fn handle(&self) -> &AppHandle<Wry> {
+        &self.handle
+    }
+
     pub async fn database(&self) -> crate::Result<Database<Wry>> {
         self.context
             .open_database("project", "project.db")
@@ -102,18 +164,68 @@ impl Project {
             .or_else(|e| Err(crate::Error::from(e)))
     }
 
+    /// Closes `project.db` so everything written to it reaches the disk. The next `database` call reopens it.
+    pub(crate) async fn flush_database(&self) -> crate::Result<()> {
+        match self.context.database("project").await {
+            Ok(database) => database.close().await.or_else(|e| Err(crate::Error::from(e))),
+            // Not opened since it was last closed.
+            Err(_) => Ok(()),
+        }
+    }
+
     pub fn id(&self) -> String {
         self.config().id
     }
+
+    /// Runs every migration step between the project's schema version and `SCHEMA_VERSION`, in order.
+    pub async fn migrate(&mut self) -> crate::Result<()> {
+        while self.config.schema_version < SCHEMA_VERSION {
+            let from = self.config.schema_version;
+            migrate_step(self, from).await?;
+
+            let mut config = self.config();
+            config.schema_version = from + 1;
+            self.update_config(config).await?;
+            log::info!("Migrated project {} to schema version {}", self.id(), from + 1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Upgrades a project from schema version `from` to `from + 1`.
+async fn migrate_step(project: &Project, from: u32) -> crate::Result<()> {
+    match from {
+        // Version 0 projects predate the factory data model, so project.db has nothing to transform.
+        0 => Ok(()),
+        _ => Err(crate::ProjectError::unsupported_schema(project.id(), from)),
+    }
+}
+
+pub(crate) fn copy_recursive(source: impl AsRef<Path>, target: impl AsRef<Path>) -> crate::Result<()> {
+    let (source, target) = (source.as_ref(), target.as_ref());
+    if source.is_dir() {
+        fs::create_dir_all(target).or_else(|e| Err(crate::Error::from(e)))?;
+        for entry in fs::read_dir(source).or_else(|e| Err(crate::Error::from(e)))? {
+            let entry = entry.or_else(|e| Err(crate::Error::from(e)))?;
+            copy_recursive(entry.path(), target.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, target).or_else(|e| Err(crate::Error::from(e)))?;
+    }
+
+    Ok(())
 }
 
 #[async_trait::async_trait]
 pub trait ProjectsExt {
     async fn projects_directory(&self) -> PathBuf;
     async fn create_project(&self, config: ProjectConfig) -> crate::Result<Project>;
-    async fn existing_project(&self, config: ProjectConfig) -> crate::Result<Project>;
+    async fn existing_project(&self, config: ProjectConfig, read_only: bool) -> crate::Result<Project>;
     async fn remove_project(&self, id: String) -> crate::Result<()>;
     async fn list_projects(&self) -> crate::Result<Vec<ProjectConfig>>;
+    async fn rename_project(&self, id: String, name: String) -> crate::Result<ProjectConfig>;
+    async fn migrate_legacy_projects(&self) -> crate::Result<()>;
 }
 
 #[async_trait::async_trait]
@@ -127,6 +239,11 @@ impl<T: Manager<tauri::Wry> + Send + Sync> ProjectsExt for T {
         if target.exists() {
             return Err(crate::ProjectError::exists(config.id));
         }
+        fs::create_dir_all(&target).or_else(|e| Err(crate::Error::from(e)))?;
+        let lock = ProjectLock::acquire(&config.id, &target)?;
+        if config.storage.is_text() {
+            TextStorage::new(&target, config.storage).initialize()?;
+        }
 
         let persistence = self.app_handle().persistence();
         let ctx = persistence
@@ -161,47 +278,99 @@ impl<T: Manager<tauri::Wry> + Send + Sync> ProjectsExt for T {
             context: ctx,
             config: config.clone(),
             handle: self.app_handle().clone(),
+            journal: Journal::begin(&target)?,
+            directory: target,
+            history: HistoryRecorder::default(),
+            lock: Some(lock),
         })
     }
-    async fn existing_project(&self, config: ProjectConfig) -> crate::Result<Project> {
+    async fn existing_project(&self, config: ProjectConfig, read_only: bool) -> crate::Result<Project> {
         let target = self.projects_directory().await.join(config.id.clone());
         if !target.exists() {
             return Err(crate::ProjectError::not_exists(config.id));
         }
+        let lock = if read_only {
+            None
+        } else {
+            Some(ProjectLock::acquire(&config.id, &target)?)
+        };
 
-        let persistence = self.app_handle().persistence();
-        let ctx = persistence
-            .context(ContextSpecifier::Direct {
-                alias: config.id.clone(),
-                path: target.clone().to_str().unwrap().to_string(),
-            })
-            .await
-            .or_else(|e| Err(crate::Error::from(e)))?;
+        // Anything failing after the lock was taken must give it back.
+        let opened = async {
+            if !read_only {
+                let settings = self.app_state().await;
+                snapshot(&target, SnapshotReason::Open)?;
+                rotate(&target, settings.backup_count, settings.backup_max_age_days)?;
+            }
 
-        let conf_handle = ctx
-            .open_file_handle("project.json", FileHandleMode::read())
-            .await
-            .or_else(|e| Err(crate::Error::from(e)))?;
-        let conf_locked = conf_handle.handle().await;
-        let mut config_file = conf_locked.lock();
-        let mut buf: Vec<u8> = Vec::new();
+            let persistence = self.app_handle().persistence();
+            let ctx = persistence
+                .context(ContextSpecifier::Direct {
+                    alias: config.id.clone(),
+                    path: target.clone().to_str().unwrap().to_string(),
+                })
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
 
-        config_file
-            .read_to_end(&mut buf)
-            .await
-            .or_else(|e| Err(crate::Error::from(e)))?;
-        let deserialized = from_slice::<ProjectConfig>(buf.as_slice())
-            .or_else(|e| Err(crate::Error::deserialization(e)))?;
-        conf_handle
-            .close()
-            .await
-            .or_else(|e| Err(crate::Error::from(e)))?;
+            let conf_handle = ctx
+                .open_file_handle("project.json", FileHandleMode::read())
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
+            let conf_locked = conf_handle.handle().await;
+            let mut config_file = conf_locked.lock();
+            let mut buf: Vec<u8> = Vec::new();
 
-        Ok(Project {
-            context: ctx,
-            config: deserialized,
-            handle: self.app_handle().clone(),
-        })
+            config_file
+                .read_to_end(&mut buf)
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
+            let deserialized = from_slice::<ProjectConfig>(buf.as_slice())
+                .or_else(|e| Err(crate::Error::deserialization(e)))?;
+            conf_handle
+                .close()
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
+
+            if deserialized.schema_version > SCHEMA_VERSION {
+                ctx.close().await.or_else(|e| Err(crate::Error::from(e)))?;
+                return Err(crate::ProjectError::unsupported_schema(
+                    deserialized.id,
+                    deserialized.schema_version,
+                ));
+            }
+
+            let needs_migration = deserialized.schema_version < SCHEMA_VERSION;
+            if needs_migration && read_only {
+                ctx.close().await.or_else(|e| Err(crate::Error::from(e)))?;
+                return Err(crate::ProjectError::read_only(deserialized.id));
+            }
+            if needs_migration {
+                snapshot(&target, SnapshotReason::Migration { from: deserialized.schema_version })?;
+            }
+
+            let mut project = Project {
+                context: ctx,
+                config: deserialized,
+                handle: self.app_handle().clone(),
+                journal: if read_only { Journal::detached(&target) } else { Journal::begin(&target)? },
+                directory: target.clone(),
+                history: HistoryRecorder::default(),
+                lock: lock.clone(),
+            };
+            if needs_migration {
+                project.migrate().await?;
+            }
+
+            Ok::<_, crate::Error>(project)
+        }
+        .await;
+        if opened.is_err() {
+            if let Some(lock) = lock.as_ref() {
+                lock.release(&target)?;
+            }
+        }
+
+        opened
     }
 
     async fn remove_project(&self, id: String) -> crate::Result<()> {
@@ -210,7 +379,7 @@ impl<T: Manager<tauri::Wry> + Send + Sync> ProjectsExt for T {
             return Err(crate::ProjectError::not_exists(id.clone()));
         }
 
-        fs::remove_dir_all(target).or_else(|e| Err(crate::Error::from(e)))?;
+        self.trash_project(id).await?;
         Ok(())
     }
 
@@ -220,6 +389,10 @@ impl<T: Manager<tauri::Wry> + Send + Sync> ProjectsExt for T {
             fs::read_dir(self.projects_directory().await).or_else(|e| Err(crate::Error::from(e)))?
         {
             if let Ok(dir) = folder {
+                // Hidden folders hold the trash and archives that are still being imported.
+                if dir.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
                 if dir.path().join("project.json").exists() {
                     if let Ok(data) = fs::read(dir.path().join("project.json")) {
                         if let Ok(deserialized) = from_slice::<ProjectConfig>(data.as_slice()) {
@@ -232,4 +405,143 @@ impl<T: Manager<tauri::Wry> + Send + Sync> ProjectsExt for T {
 
         Ok(results)
     }
+
+    async fn rename_project(&self, id: String, name: String) -> crate::Result<ProjectConfig> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(crate::ProjectError::invalid_name(name));
+        }
+
+        let config_path = self.projects_directory().await.join(id.clone()).join("project.json");
+        if !config_path.exists() {
+            return Err(crate::ProjectError::not_exists(id));
+        }
+        ProjectLock::check(&id, config_path.parent().unwrap())?;
+
+        let data = fs::read(config_path.clone()).or_else(|e| Err(crate::Error::from(e)))?;
+        let mut config = from_slice::<ProjectConfig>(data.as_slice())
+            .or_else(|e| Err(crate::Error::deserialization(e)))?;
+        config.name = name;
+
+        fs::write(
+            config_path,
+            to_string_pretty(&config).or_else(|e| Err(crate::Error::serialization(e)))?,
+        )
+        .or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(config)
+    }
+
+    /// Moves every legacy project to a UUID folder. Runs once at startup; a project that can't be migrated is
+    /// logged and left as it is.
+    async fn migrate_legacy_projects(&self) -> crate::Result<()> {
+        let projects_dir = self.projects_directory().await;
+        for folder in fs::read_dir(projects_dir.clone()).or_else(|e| Err(crate::Error::from(e)))? {
+            let Ok(dir) = folder else {
+                continue;
+            };
+            if let Err(e) = migrate_legacy_project(&projects_dir, dir.path()) {
+                log::warn!("Failed to migrate legacy project in {}: {e}", dir.path().display());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Moves a project created before ids were UUIDs into a folder named after a new UUID id, returning that id, or
+/// `None` if `folder` doesn't hold a legacy project. The folder is renamed before its config is rewritten, and
+/// renamed back if that fails, so an interrupted migration is retried on the next start. Fails if another instance
+/// has the project open.
+fn migrate_legacy_project(projects_dir: &Path, folder: PathBuf) -> crate::Result<Option<String>> {
+    let Ok(data) = fs::read(folder.join("project.json")) else {
+        return Ok(None);
+    };
+    let Ok(mut config) = from_slice::<ProjectConfig>(data.as_slice()) else {
+        return Ok(None);
+    };
+    if !config.is_legacy() {
+        return Ok(None);
+    }
+
+    let legacy_id = config.id.clone();
+    ProjectLock::check(&legacy_id, &folder)?;
+    config.id = Uuid::new_v4().to_string();
+    let target = projects_dir.join(config.id.clone());
+    fs::rename(&folder, &target).or_else(|e| Err(crate::Error::from(e)))?;
+
+    let written = to_string_pretty(&config)
+        .or_else(|e| Err(crate::Error::serialization(e)))
+        .and_then(|data| {
+            let partial = target.join("project.json.partial");
+            fs::write(&partial, data)?;
+            fs::rename(partial, target.join("project.json"))?;
+            Ok(())
+        });
+    if let Err(e) = written {
+        fs::rename(&target, &folder).or_else(|e| Err(crate::Error::from(e)))?;
+        return Err(e);
+    }
+
+    log::info!("Migrated legacy project {legacy_id} to {}", config.id);
+    Ok(Some(config.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(folder: &Path, config: &ProjectConfig) {
+        fs::create_dir_all(folder).unwrap();
+        fs::write(folder.join("project.json"), to_string_pretty(config).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn migrate_legacy_folder() -> crate::Result<()> {
+        let projects = tempfile::tempdir()?;
+        let mut legacy = ProjectConfig::new("My Factory", "1.0-stable");
+        legacy.id = String::from("my_factory");
+        write_config(&projects.path().join("my_factory"), &legacy);
+        fs::write(projects.path().join("my_factory").join("project.db"), "records")?;
+
+        let id = migrate_legacy_project(projects.path(), projects.path().join("my_factory"))?.unwrap();
+        assert!(!projects.path().join("my_factory").exists());
+
+        let migrated = projects.path().join(&id);
+        let config = from_slice::<ProjectConfig>(fs::read(migrated.join("project.json"))?.as_slice()).unwrap();
+        assert_eq!(config.id, id);
+        assert_eq!(config.name, "My Factory");
+        assert!(!config.is_legacy());
+        assert_eq!(fs::read_to_string(migrated.join("project.db"))?, "records");
+        Ok(())
+    }
+
+    #[test]
+    fn skip_current_projects() -> crate::Result<()> {
+        let projects = tempfile::tempdir()?;
+        let current = ProjectConfig::new("Current", "1.0-stable");
+        write_config(&projects.path().join(&current.id), &current);
+        fs::create_dir_all(projects.path().join("not_a_project"))?;
+
+        assert_eq!(migrate_legacy_project(projects.path(), projects.path().join(&current.id))?, None);
+        assert_eq!(migrate_legacy_project(projects.path(), projects.path().join("not_a_project"))?, None);
+        assert!(projects.path().join(&current.id).join("project.json").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn skip_locked_legacy_folder() -> crate::Result<()> {
+        let projects = tempfile::tempdir()?;
+        let mut legacy = ProjectConfig::new("My Factory", "1.0-stable");
+        legacy.id = String::from("my_factory");
+        let folder = projects.path().join("my_factory");
+        write_config(&folder, &legacy);
+        let lock = ProjectLock::acquire(&legacy.id, &folder)?;
+
+        assert!(migrate_legacy_project(projects.path(), folder.clone()).is_err());
+        assert!(folder.join("project.json").exists());
+
+        lock.release(&folder)?;
+        assert!(migrate_legacy_project(projects.path(), folder.clone())?.is_some());
+        Ok(())
+    }
 }
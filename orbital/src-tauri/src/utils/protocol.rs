@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tauri::{
+    http::{header, Request, Response, StatusCode},
+    AppHandle, Manager, Runtime, UriSchemeContext, UriSchemeResponder,
+};
+use zip::ZipArchive;
+
+use super::{
+    assets::{read_archive_entry, AssetSource},
+    AssetsExt,
+};
+
+pub const SCHEME: &str = "orbital";
+
+/// Served in place of icons that weren't extracted into the pack.
+const PLACEHOLDER: &[u8] = include_bytes!("../../assets/placeholder.png");
+const CACHE_IMMUTABLE: &str = "public, max-age=604800, immutable";
+const CACHE_NONE: &str = "no-cache";
+
+/// Pack archives opened by the protocol handler, kept open so each request doesn't re-read the central directory.
+#[derive(Clone, Default)]
+pub struct PackArchives(Arc<Mutex<HashMap<PathBuf, ZipArchive<fs::File>>>>);
+
+impl PackArchives {
+    pub fn attach<R: Runtime>(handle: &AppHandle<R>) {
+        handle.manage(Self::default());
+    }
+
+    fn read(&self, path: PathBuf, relative: &str) -> crate::Result<Option<Vec<u8>>> {
+        let mut archives = self.0.lock().unwrap();
+        if !archives.contains_key(&path) {
+            let file = fs::File::open(path.clone()).or_else(|e| Err(crate::Error::from(e)))?;
+            let archive = ZipArchive::new(file)
+                .or_else(|e| Err(crate::AssetVersionError::invalid_pack(e.to_string())))?;
+            archives.insert(path.clone(), archive);
+        }
+
+        read_archive_entry(archives.get_mut(&path).unwrap(), relative)
+    }
+
+    /// Drops a cached archive, i.e. after its asset version was uninstalled.
+    pub fn forget(&self, path: &Path) {
+        self.0.lock().unwrap().remove(path);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AssetRequest {
+    Icon { version: String, id: Option<String> },
+    Map { version: String, slice: String },
+}
+
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Whether a decoded segment names a single file or folder. Anything that could step outside the pack root once
+/// joined onto a path is refused.
+fn is_plain_segment(segment: &str) -> bool {
+    !segment.contains(['/', '\\']) && !segment.contains("..")
+}
+
+/// Parses `orbital://<version>/<kind>/<name>`. Platforms that serve custom schemes as
+/// `http://orbital.localhost/...` put the version in the first path segment instead.
+fn parse_request<B>(request: &Request<B>) -> Option<AssetRequest> {
+    let uri = request.uri();
+    let mut segments: Vec<String> = uri
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(percent_decode)
+        .collect();
+
+    let host = uri.host().unwrap_or_default();
+    let version = if host.is_empty() || host == "localhost" || host.ends_with(".localhost") {
+        if segments.is_empty() {
+            return None;
+        }
+        segments.remove(0)
+    } else {
+        percent_decode(host)
+    };
+
+    if !is_plain_segment(&version) || !segments.iter().all(|segment| is_plain_segment(segment)) {
+        return None;
+    }
+
+    let name = segments.get(1).map(|name| name.trim_end_matches(".png").to_string());
+    match segments.first().map(|kind| kind.as_str()) {
+        Some("icon") if segments.len() <= 2 => Some(AssetRequest::Icon {
+            version,
+            id: name.filter(|id| !id.is_empty()),
+        }),
+        Some("map") if segments.len() == 2 => Some(AssetRequest::Map { version, slice: name? }),
+        _ => None,
+    }
+}
+
+fn respond(status: StatusCode, body: Vec<u8>, cache: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, if status.is_success() { "image/png" } else { "text/plain" })
+        .header(header::CACHE_CONTROL, cache)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(body)
+        .unwrap()
+}
+
+fn placeholder() -> Response<Vec<u8>> {
+    let mut response = respond(StatusCode::OK, PLACEHOLDER.to_vec(), CACHE_NONE);
+    response
+        .headers_mut()
+        .insert("x-orbital-placeholder", header::HeaderValue::from_static("true"));
+    response
+}
+
+fn not_found(reason: impl AsRef<str>) -> Response<Vec<u8>> {
+    respond(StatusCode::NOT_FOUND, reason.as_ref().as_bytes().to_vec(), CACHE_NONE)
+}
+
+fn read_asset<R: Runtime>(handle: &AppHandle<R>, version: &str, relative: &str) -> crate::Result<Option<Vec<u8>>> {
+    match handle.asset_source(version)? {
+        AssetSource::Directory(path) => AssetSource::Directory(path).read(relative),
+        AssetSource::Archive(path) => handle.state::<PackArchives>().read(path, relative),
+    }
+}
+
+pub fn handle_request<R: Runtime>(handle: &AppHandle<R>, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let Some(asset) = parse_request(request) else {
+        return respond(StatusCode::BAD_REQUEST, b"Expected orbital://<version>/icon/<id> or orbital://<version>/map/<slice>".to_vec(), CACHE_NONE);
+    };
+
+    match asset {
+        AssetRequest::Icon { version, id: None } => {
+            if handle.asset_source(version.as_str()).is_err() {
+                return not_found(format!("Unknown asset version {version}"));
+            }
+            placeholder()
+        }
+        AssetRequest::Icon { version, id: Some(id) } => {
+            match read_asset(handle, version.as_str(), format!("icons/{id}.png").as_str()) {
+                Ok(Some(data)) => respond(StatusCode::OK, data, CACHE_IMMUTABLE),
+                Ok(None) => placeholder(),
+                Err(e) => not_found(e.to_string()),
+            }
+        }
+        AssetRequest::Map { version, slice } => {
+            match read_asset(handle, version.as_str(), format!("map/{slice}.png").as_str()) {
+                Ok(Some(data)) => respond(StatusCode::OK, data, CACHE_IMMUTABLE),
+                Ok(None) => not_found(format!("Unknown map slice {slice}")),
+                Err(e) => not_found(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Entry point registered with `register_asynchronous_uri_scheme_protocol`. Reads happen off the main thread.
+pub fn protocol_handler<R: Runtime>(ctx: UriSchemeContext<'_, R>, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let handle = ctx.app_handle().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        responder.respond(handle_request(&handle, &request));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(uri: &str) -> Option<AssetRequest> {
+        parse_request(&Request::builder().uri(uri).body(()).unwrap())
+    }
+
+    #[test]
+    fn parse_icons_and_slices() {
+        assert_eq!(
+            parse("orbital://1.0/icon/Desc_IronIngot_C.png"),
+            Some(AssetRequest::Icon { version: "1.0".to_string(), id: Some("Desc_IronIngot_C".to_string()) })
+        );
+        assert_eq!(
+            parse("http://orbital.localhost/1.0/map/0_0.png"),
+            Some(AssetRequest::Map { version: "1.0".to_string(), slice: "0_0".to_string() })
+        );
+        assert_eq!(parse("orbital://1.0/icon"), Some(AssetRequest::Icon { version: "1.0".to_string(), id: None }));
+    }
+
+    #[test]
+    fn reject_traversal() {
+        assert_eq!(parse("orbital://1.0/icon/..%2F..%2Fproject"), None);
+        assert_eq!(parse("orbital://1.0/map/..%5Csecret"), None);
+        assert_eq!(parse("http://orbital.localhost/..%2F..%2Fhome/icon/Desc_IronIngot_C"), None);
+        assert_eq!(parse("orbital://1.0/icon/%2E%2E"), None);
+    }
+}
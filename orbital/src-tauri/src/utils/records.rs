@@ -0,0 +1,224 @@
+use bson::{doc, to_document, Bson, Document};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{
+    Factory, HistoryEntry, JournalOp, LineLink, Note, ProductionLine, Project, RecordChange,
+    StorageFormat, TextStorage,
+};
+
+/// A document stored in one of the collections of a project's `project.db`.
+pub trait Record: Serialize + DeserializeOwned + Clone + Send + Sync + 'static {
+    const COLLECTION: &'static str;
+
+    fn id(&self) -> String;
+}
+
+/// Every collection a project stores, used when moving records between storage formats.
+pub const COLLECTIONS: [&str; 5] = [
+    Factory::COLLECTION,
+    ProductionLine::COLLECTION,
+    LineLink::COLLECTION,
+    Note::COLLECTION,
+    HistoryEntry::COLLECTION,
+];
+
+fn from_value<T: Record>(value: Value) -> crate::Result<T> {
+    serde_json::from_value::<T>(value).or_else(|e| Err(crate::Error::deserialization(e)))
+}
+
+fn to_value<T: Record>(record: &T) -> crate::Result<Value> {
+    serde_json::to_value(record).or_else(|e| Err(crate::Error::serialization(e)))
+}
+
+impl Project {
+    /// Text storage of this project, or `None` if it keeps its records in `project.db`.
+    async fn text_storage(&self, format: StorageFormat) -> Option<TextStorage> {
+        if format.is_text() {
+            Some(TextStorage::new(self.directory(), format))
+        } else {
+            None
+        }
+    }
+
+    pub async fn records<T: Record>(&self) -> crate::Result<Vec<T>> {
+        self.find_records::<T>(doc! {}).await
+    }
+
+    pub async fn find_records<T: Record>(&self, filter: Document) -> crate::Result<Vec<T>> {
+        if let Some(storage) = self.text_storage(self.config().storage).await {
+            return storage
+                .find(T::COLLECTION, &filter)?
+                .into_iter()
+                .map(from_value::<T>)
+                .collect();
+        }
+
+        self.database()
+            .await?
+            .collection::<T>(T::COLLECTION)
+            .await
+            .find(filter, None, None, None)
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))
+    }
+
+    pub async fn get_record<T: Record>(&self, id: impl AsRef<str>) -> crate::Result<T> {
+        let found = if let Some(storage) = self.text_storage(self.config().storage).await {
+            storage.get(T::COLLECTION, id.as_ref())?.map(from_value::<T>).transpose()?
+        } else {
+            self.database()
+                .await?
+                .collection::<T>(T::COLLECTION)
+                .await
+                .find_one(doc! {"id": id.as_ref()})
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?
+        };
+
+        found.ok_or(crate::ProjectError::record_not_exists(T::COLLECTION, id))
+    }
+
+    pub async fn insert_record<T: Record>(&self, record: T) -> crate::Result<T> {
+        self.ensure_writable()?;
+        self.journal().record(JournalOp::upsert(&record)?)?;
+        self.history().push(RecordChange::of::<T>(record.id(), None, Some(&record))?);
+
+        if let Some(storage) = self.text_storage(self.config().storage).await {
+            storage.put(T::COLLECTION, &record.id(), &to_value(&record)?)?;
+            return Ok(record);
+        }
+
+        self.database()
+            .await?
+            .collection::<T>(T::COLLECTION)
+            .await
+            .insert_one(record.clone())
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(record)
+    }
+
+    pub async fn save_record<T: Record>(&self, record: T) -> crate::Result<T> {
+        self.ensure_writable()?;
+        let existing = self.get_record::<T>(record.id()).await?;
+        let update = to_document(&record).or_else(|e| Err(crate::Error::serialization(e)))?;
+        self.journal().record(JournalOp::upsert(&record)?)?;
+        self.history().push(RecordChange::of::<T>(record.id(), Some(&existing), Some(&record))?);
+
+        if let Some(storage) = self.text_storage(self.config().storage).await {
+            storage.put(T::COLLECTION, &record.id(), &to_value(&record)?)?;
+            return Ok(record);
+        }
+
+        self.database()
+            .await?
+            .collection::<T>(T::COLLECTION)
+            .await
+            .update_one(doc! {"id": record.id()}, doc! {"$set": update})
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(record)
+    }
+
+    pub async fn remove_record<T: Record>(&self, id: impl AsRef<str>) -> crate::Result<T> {
+        self.ensure_writable()?;
+        let existing = self.get_record::<T>(id.as_ref()).await?;
+        self.journal().record(JournalOp::remove::<T>(id.as_ref()))?;
+        self.history().push(RecordChange::of::<T>(id.as_ref(), Some(&existing), None)?);
+
+        if let Some(storage) = self.text_storage(self.config().storage).await {
+            storage.remove(T::COLLECTION, id.as_ref())?;
+            return Ok(existing);
+        }
+
+        self.database()
+            .await?
+            .collection::<T>(T::COLLECTION)
+            .await
+            .delete_one(doc! {"id": id.as_ref()})
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(existing)
+    }
+
+    /// Applies a journaled edit directly to storage, bypassing typed records and the edit history.
+    pub(crate) async fn apply_op(&self, op: &JournalOp) -> crate::Result<()> {
+        self.ensure_writable()?;
+        self.journal().record(op.clone())?;
+
+        let format = self.config().storage;
+        match op {
+            JournalOp::Upsert { collection, id, record } => self.raw_upsert(format, collection, id, record).await,
+            JournalOp::Remove { collection, id } => self.raw_remove(format, collection, id).await,
+        }
+    }
+
+    /// Every record of `collection` in the given storage format, as JSON.
+    pub(crate) async fn raw_records(&self, format: StorageFormat, collection: &str) -> crate::Result<Vec<Value>> {
+        if let Some(storage) = self.text_storage(format).await {
+            return storage.list(collection);
+        }
+
+        let documents = self
+            .database()
+            .await?
+            .collection::<Document>(collection)
+            .await
+            .find(doc! {}, None, None, None)
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+
+        Ok(documents
+            .into_iter()
+            .map(|mut document| {
+                // Internal database key, not part of the record.
+                document.remove("_id");
+                Bson::Document(document).into_relaxed_extjson()
+            })
+            .collect())
+    }
+
+    pub(crate) async fn raw_upsert(&self, format: StorageFormat, collection: &str, id: &str, record: &Value) -> crate::Result<()> {
+        if let Some(storage) = self.text_storage(format).await {
+            return storage.put(collection, id, record);
+        }
+
+        let database = self.database().await?;
+        let document = to_document(record).or_else(|e| Err(crate::Error::serialization(e)))?;
+        let collection = database.collection::<Document>(collection).await;
+        let existing = collection
+            .find_one(doc! {"id": id})
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+
+        if existing.is_some() {
+            collection
+                .update_one(doc! {"id": id}, doc! {"$set": document})
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
+        } else {
+            collection
+                .insert_one(document)
+                .await
+                .or_else(|e| Err(crate::Error::from(e)))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn raw_remove(&self, format: StorageFormat, collection: &str, id: &str) -> crate::Result<()> {
+        if let Some(storage) = self.text_storage(format).await {
+            return storage.remove(collection, id);
+        }
+
+        self.database()
+            .await?
+            .collection::<Document>(collection)
+            .await
+            .delete_one(doc! {"id": id})
+            .await
+            .or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(())
+    }
+}
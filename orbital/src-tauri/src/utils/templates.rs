@@ -0,0 +1,344 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::PathBuf,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, from_str, to_string_pretty, Value};
+use specta::Type;
+use tauri::Manager;
+use uuid::Uuid;
+
+use super::{Factory, JournalOp, LineLink, Note, ProductionLine, Project, Record};
+
+/// Folder in the app data directory that holds templates saved from projects.
+pub const TEMPLATES_DIRECTORY: &str = "templates";
+
+/// Templates shipped with the app, as `(id, definition)`.
+const BUNDLED_TEMPLATES: [(&str, &str); 2] = [
+    ("early-game-starter", include_str!("../../templates/early-game-starter.json")),
+    ("resource-node-tracker", include_str!("../../templates/resource-node-tracker.json")),
+];
+
+/// Collections copied into and out of templates. History is never part of a template.
+const TEMPLATE_COLLECTIONS: [&str; 4] = [
+    Factory::COLLECTION,
+    ProductionLine::COLLECTION,
+    LineLink::COLLECTION,
+    Note::COLLECTION,
+];
+
+/// Record fields that hold the id of another record, remapped along with the ids when a template is applied.
+const REFERENCE_FIELDS: [&str; 4] = ["factory_id", "line_id", "source", "target"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateSource {
+    Bundled,
+    User,
+}
+
+/// A set of records a new project starts with. Record ids only need to be unique within the template; every
+/// project created from it gets fresh ids.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    /// Asset version of the project the template was saved from. Bundled templates work with any version.
+    #[serde(default)]
+    pub asset_version: Option<String>,
+
+    /// Records keyed by collection.
+    #[serde(default)]
+    pub records: BTreeMap<String, Vec<Value>>,
+}
+
+/// A template as listed to the frontend, without its records.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TemplateInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub asset_version: Option<String>,
+    pub source: TemplateSource,
+    pub records: u32,
+}
+
+impl ProjectTemplate {
+    pub fn info(&self, source: TemplateSource) -> TemplateInfo {
+        TemplateInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            asset_version: self.asset_version.clone(),
+            source,
+            records: self.records.values().map(|records| records.len() as u32).sum(),
+        }
+    }
+
+    /// Checks that every record of the template reads back as its collection's record type.
+    pub fn validate(&self) -> crate::Result<()> {
+        fn check<T: Record>(record: &Value) -> crate::Result<()> {
+            serde_json::from_value::<T>(record.clone())
+                .map(|_| ())
+                .or_else(|e| Err(crate::ProjectError::invalid_record(T::COLLECTION, e.to_string())))
+        }
+
+        for (collection, records) in self.records.iter() {
+            for record in records {
+                match collection.as_str() {
+                    Factory::COLLECTION => check::<Factory>(record)?,
+                    ProductionLine::COLLECTION => check::<ProductionLine>(record)?,
+                    LineLink::COLLECTION => check::<LineLink>(record)?,
+                    Note::COLLECTION => check::<Note>(&with_timestamps(record.clone()))?,
+                    _ => return Err(crate::ProjectError::invalid_record(collection, "Not a template collection")),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Notes in templates carry no timestamps; they are created when the template is applied.
+fn with_timestamps(mut record: Value) -> Value {
+    if let Some(fields) = record.as_object_mut() {
+        let now = serde_json::to_value(Utc::now()).unwrap_or(Value::Null);
+        fields.insert(String::from("created"), now.clone());
+        fields.insert(String::from("updated"), now);
+    }
+    record
+}
+
+impl ProjectTemplate {
+    /// The template's records as `(collection, record)` with a new id for every record. References between records
+    /// follow their new ids; references to records outside the template are left alone.
+    fn fresh_records(&self) -> Vec<(&'static str, Value)> {
+        let ids: HashMap<String, String> = self
+            .records
+            .values()
+            .flatten()
+            .filter_map(|record| record.get("id").and_then(Value::as_str))
+            .map(|id| (id.to_string(), Uuid::new_v4().to_string()))
+            .collect();
+
+        let mut fresh = Vec::new();
+        for collection in TEMPLATE_COLLECTIONS {
+            for record in self.records.get(collection).into_iter().flatten() {
+                let mut record = record.clone();
+                if collection == Note::COLLECTION {
+                    record = with_timestamps(record);
+                }
+                let Some(fields) = record.as_object_mut() else {
+                    continue;
+                };
+                for field in std::iter::once("id").chain(REFERENCE_FIELDS) {
+                    if let Some(mapped) = fields.get(field).and_then(Value::as_str).and_then(|old| ids.get(old)) {
+                        fields.insert(field.to_string(), Value::String(mapped.clone()));
+                    }
+                }
+                fresh.push((collection, record));
+            }
+        }
+
+        fresh
+    }
+}
+
+impl Project {
+    /// Seeds the project with the records of a template, giving each record a new id.
+    pub async fn apply_template(&self, template: &ProjectTemplate) -> crate::Result<u32> {
+        template.validate()?;
+
+        let mut applied = 0;
+        for (collection, record) in template.fresh_records() {
+            let id = record.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+            self.apply_op(&JournalOp::Upsert {
+                collection: collection.to_string(),
+                id,
+                record,
+            })
+            .await?;
+            applied += 1;
+        }
+
+        Ok(applied)
+    }
+
+    /// Captures the project's records as a user template.
+    pub async fn to_template(&self, name: impl AsRef<str>, description: impl AsRef<str>) -> crate::Result<ProjectTemplate> {
+        let mut records = BTreeMap::new();
+        for collection in TEMPLATE_COLLECTIONS {
+            records.insert(collection.to_string(), self.raw_records(self.config().storage, collection).await?);
+        }
+
+        Ok(ProjectTemplate {
+            id: Uuid::new_v4().to_string(),
+            name: name.as_ref().trim().to_string(),
+            description: description.as_ref().to_string(),
+            asset_version: Some(self.config().asset_version),
+            records,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+pub trait TemplatesExt {
+    fn templates_directory(&self) -> PathBuf;
+    async fn list_templates(&self) -> crate::Result<Vec<TemplateInfo>>;
+    async fn template(&self, id: String) -> crate::Result<ProjectTemplate>;
+    async fn save_template(&self, template: ProjectTemplate) -> crate::Result<TemplateInfo>;
+    async fn remove_template(&self, id: String) -> crate::Result<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: Manager<tauri::Wry> + Send + Sync> TemplatesExt for T {
+    fn templates_directory(&self) -> PathBuf {
+        self.path()
+            .app_data_dir()
+            .expect("Should be able to retrieve app data dir.")
+            .join(TEMPLATES_DIRECTORY)
+    }
+
+    /// Lists bundled templates first, then user templates by name.
+    async fn list_templates(&self) -> crate::Result<Vec<TemplateInfo>> {
+        let mut results: Vec<TemplateInfo> = Vec::new();
+        for (_, definition) in BUNDLED_TEMPLATES {
+            let template = from_str::<ProjectTemplate>(definition).or_else(|e| Err(crate::Error::deserialization(e)))?;
+            results.push(template.info(TemplateSource::Bundled));
+        }
+
+        let directory = self.templates_directory();
+        if !directory.exists() {
+            return Ok(results);
+        }
+
+        let mut saved: Vec<TemplateInfo> = Vec::new();
+        for entry in fs::read_dir(directory).or_else(|e| Err(crate::Error::from(e)))? {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let Ok(data) = fs::read(entry.path()) else {
+                continue;
+            };
+            if let Ok(template) = from_slice::<ProjectTemplate>(data.as_slice()) {
+                saved.push(template.info(TemplateSource::User));
+            }
+        }
+
+        saved.sort_by(|a, b| a.name.cmp(&b.name));
+        results.extend(saved);
+        Ok(results)
+    }
+
+    async fn template(&self, id: String) -> crate::Result<ProjectTemplate> {
+        if let Some((_, definition)) = BUNDLED_TEMPLATES.iter().find(|(bundled, _)| *bundled == id) {
+            return from_str::<ProjectTemplate>(definition).or_else(|e| Err(crate::Error::deserialization(e)));
+        }
+        if id.is_empty() || id.contains(['/', '\\', '.']) {
+            return Err(crate::ProjectError::template_not_exists(id));
+        }
+
+        let data = fs::read(self.templates_directory().join(format!("{id}.json")))
+            .or(Err(crate::ProjectError::template_not_exists(id)))?;
+        from_slice::<ProjectTemplate>(data.as_slice()).or_else(|e| Err(crate::Error::deserialization(e)))
+    }
+
+    async fn save_template(&self, template: ProjectTemplate) -> crate::Result<TemplateInfo> {
+        if template.name.is_empty() {
+            return Err(crate::ProjectError::invalid_name(template.name));
+        }
+        template.validate()?;
+
+        let directory = self.templates_directory();
+        fs::create_dir_all(&directory).or_else(|e| Err(crate::Error::from(e)))?;
+        fs::write(
+            directory.join(format!("{}.json", template.id)),
+            to_string_pretty(&template).or_else(|e| Err(crate::Error::serialization(e)))?,
+        )
+        .or_else(|e| Err(crate::Error::from(e)))?;
+
+        Ok(template.info(TemplateSource::User))
+    }
+
+    /// Deletes a user template. Bundled templates cannot be removed.
+    async fn remove_template(&self, id: String) -> crate::Result<()> {
+        let path = self.templates_directory().join(format!("{id}.json"));
+        if id.contains(['/', '\\', '.']) || !path.exists() {
+            return Err(crate::ProjectError::template_not_exists(id));
+        }
+
+        fs::remove_file(path).or_else(|e| Err(crate::Error::from(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> ProjectTemplate {
+        from_str(
+            r#"{
+                "id": "test",
+                "name": "Test",
+                "records": {
+                    "factories": [{ "id": "f1", "name": "Smelting" }],
+                    "production_lines": [
+                        { "id": "l1", "factory_id": "f1", "recipe": "Recipe_IngotIron_C" },
+                        { "id": "l2", "factory_id": "f1", "recipe": "Recipe_IronPlate_C" }
+                    ],
+                    "line_links": [{ "id": "k1", "source": "l1", "target": "l2", "item": "Desc_IronIngot_C" }],
+                    "notes": [{ "id": "n1", "line_id": "l2", "factory_id": "elsewhere", "text": "Check belts" }]
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn field<'a>(records: &'a [(&str, Value)], collection: &str, index: usize, name: &str) -> &'a str {
+        records.iter().filter(|(c, _)| *c == collection).nth(index).unwrap().1[name].as_str().unwrap()
+    }
+
+    #[test]
+    fn fresh_records_remap_ids() {
+        let template = template();
+        let records = template.fresh_records();
+        let collections: Vec<&str> = records.iter().map(|(collection, _)| *collection).collect();
+        assert_eq!(collections, vec!["factories", "production_lines", "production_lines", "line_links", "notes"]);
+
+        let ids: Vec<&str> = records.iter().map(|(_, record)| record["id"].as_str().unwrap()).collect();
+        assert!(ids.iter().all(|id| Uuid::parse_str(id).is_ok()));
+        assert!(ids.iter().enumerate().all(|(i, id)| !ids[i + 1..].contains(id)));
+
+        let factory = field(&records, "factories", 0, "id");
+        let first = field(&records, "production_lines", 0, "id");
+        let second = field(&records, "production_lines", 1, "id");
+        assert_eq!(field(&records, "production_lines", 0, "factory_id"), factory);
+        assert_eq!(field(&records, "production_lines", 1, "factory_id"), factory);
+        assert_eq!(field(&records, "line_links", 0, "source"), first);
+        assert_eq!(field(&records, "line_links", 0, "target"), second);
+        assert_eq!(field(&records, "line_links", 0, "item"), "Desc_IronIngot_C");
+        assert_eq!(field(&records, "notes", 0, "line_id"), second);
+        assert_eq!(field(&records, "notes", 0, "factory_id"), "elsewhere");
+        assert!(records[4].1.get("created").is_some());
+
+        let again = template.fresh_records();
+        assert_ne!(field(&again, "factories", 0, "id"), factory);
+    }
+
+    #[test]
+    fn bundled_templates_are_valid() {
+        for (id, definition) in BUNDLED_TEMPLATES {
+            let template: ProjectTemplate = from_str(definition).unwrap();
+            assert_eq!(template.id, id);
+            template.validate().unwrap();
+            assert_eq!(template.fresh_records().len() as u32, template.info(TemplateSource::Bundled).records);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bson::{to_document, Document};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use super::{backups::snapshot, Project, SnapshotReason, COLLECTIONS};
+
+/// Folder inside a project that holds its records when it uses a text storage format.
+pub const RECORDS_DIRECTORY: &str = "records";
+
+/// Files that only matter to the running app, ignored when a text project is kept in git.
+const GITIGNORE: &str = "# Written by Orbital. Only project.json and records/ describe the plan.
+project.db/
+project.db
+project.lock
+.session
+journal*.jsonl
+backups/
+records/history/
+";
+
+/// Where a project keeps its records.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    /// A single `project.db` database.
+    #[default]
+    Database,
+
+    /// One pretty-printed JSON file per record under `records/<collection>/`.
+    Json,
+
+    /// One TOML file per record under `records/<collection>/`.
+    Toml,
+}
+
+impl StorageFormat {
+    pub fn is_text(&self) -> bool {
+        !matches!(self, Self::Database)
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Database => "db",
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// Drops `null` fields, which TOML cannot represent. Missing optional fields read back as `None`.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(without_nulls).collect()),
+        other => other,
+    }
+}
+
+/// Reads and writes records as individual text files. Records are written from a `serde_json::Value`, so field
+/// order is fixed and saving an unchanged record rewrites the identical file.
+#[derive(Clone, Debug)]
+pub struct TextStorage {
+    root: PathBuf,
+    format: StorageFormat,
+}
+
+impl TextStorage {
+    /// Text storage of the project in `directory`.
+    pub fn new(directory: impl AsRef<Path>, format: StorageFormat) -> Self {
+        Self {
+            root: directory.as_ref().join(RECORDS_DIRECTORY),
+            format,
+        }
+    }
+
+    /// Creates the records folder and a `.gitignore` for the app's runtime files next to it.
+    pub fn initialize(&self) -> crate::Result<()> {
+        fs::create_dir_all(&self.root)?;
+        if let Some(project) = self.root.parent() {
+            if !project.join(".gitignore").exists() {
+                fs::write(project.join(".gitignore"), GITIGNORE)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, collection: &str, id: &str) -> crate::Result<PathBuf> {
+        if id.is_empty() || id.contains(['/', '\\', '.']) {
+            return Err(crate::ProjectError::invalid_record(collection, format!("Invalid record id {id:?}")));
+        }
+
+        Ok(self.root.join(collection).join(format!("{id}.{}", self.format.extension())))
+    }
+
+    fn encode(&self, collection: &str, record: &Value) -> crate::Result<String> {
+        let encoded = match self.format {
+            StorageFormat::Toml => toml::to_string_pretty(&without_nulls(record.clone()))
+                .or_else(|e| Err(crate::ProjectError::invalid_record(collection, e.to_string())))?,
+            _ => serde_json::to_string_pretty(record).or_else(|e| Err(crate::Error::serialization(e)))?,
+        };
+
+        Ok(format!("{}\n", encoded.trim_end()))
+    }
+
+    fn decode(&self, collection: &str, data: &str) -> crate::Result<Value> {
+        match self.format {
+            StorageFormat::Toml => toml::from_str::<Value>(data)
+                .or_else(|e| Err(crate::ProjectError::invalid_record(collection, e.to_string()))),
+            _ => serde_json::from_str::<Value>(data).or_else(|e| Err(crate::Error::deserialization(e))),
+        }
+    }
+
+    /// Every record of a collection, ordered by file name.
+    pub fn list(&self, collection: &str) -> crate::Result<Vec<Value>> {
+        let folder = self.root.join(collection);
+        if !folder.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(folder)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == self.format.extension()))
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| self.decode(collection, fs::read_to_string(path)?.as_str()))
+            .collect()
+    }
+
+    /// Records whose top-level fields equal every field of `filter`.
+    pub fn find(&self, collection: &str, filter: &Document) -> crate::Result<Vec<Value>> {
+        let mut results = Vec::new();
+        for record in self.list(collection)? {
+            let document = to_document(&record).or_else(|e| Err(crate::Error::serialization(e)))?;
+            if filter.iter().all(|(key, value)| document.get(key) == Some(value)) {
+                results.push(record);
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub fn get(&self, collection: &str, id: &str) -> crate::Result<Option<Value>> {
+        let path = self.path(collection, id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.decode(collection, fs::read_to_string(path)?.as_str())?))
+    }
+
+    /// Writes a record, replacing the file atomically.
+    pub fn put(&self, collection: &str, id: &str, record: &Value) -> crate::Result<()> {
+        let path = self.path(collection, id)?;
+        fs::create_dir_all(self.root.join(collection))?;
+
+        let partial = path.with_extension("partial");
+        fs::write(&partial, self.encode(collection, record)?)?;
+        fs::rename(partial, path)?;
+        Ok(())
+    }
+
+    pub fn remove(&self, collection: &str, id: &str) -> crate::Result<()> {
+        let path = self.path(collection, id)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Deletes the records a project kept in `from`, once it has moved to `to`. Text formats share `records/`, so only
+/// the files of the old format are removed, along with folders that end up empty. The project must be closed.
+pub fn discard_storage(directory: impl AsRef<Path>, from: StorageFormat, to: StorageFormat) -> crate::Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    if !from.is_text() {
+        let database = directory.as_ref().join("project.db");
+        if database.is_dir() {
+            fs::remove_dir_all(database)?;
+        } else if database.exists() {
+            fs::remove_file(database)?;
+        }
+        return Ok(());
+    }
+
+    let root = directory.as_ref().join(RECORDS_DIRECTORY);
+    if !root.exists() {
+        return Ok(());
+    }
+    for collection in fs::read_dir(&root)?.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if !collection.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&collection)?.filter_map(|entry| entry.ok()) {
+            if entry.path().extension().is_some_and(|ext| ext == from.extension()) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        if fs::read_dir(&collection)?.next().is_none() {
+            fs::remove_dir(&collection)?;
+        }
+    }
+    if fs::read_dir(&root)?.next().is_none() {
+        fs::remove_dir(&root)?;
+    }
+
+    Ok(())
+}
+
+impl Project {
+    /// Copies every record into `format` and switches the project over to it, snapshotting the project first.
+    /// The old records stay in place until `discard_storage` is called for them. Returns the number of records copied.
+    pub async fn convert_storage(&mut self, format: StorageFormat) -> crate::Result<u32> {
+        self.ensure_writable()?;
+        let from = self.config().storage;
+        if from == format {
+            return Ok(0);
+        }
+
+        let directory = self.directory();
+        snapshot(&directory, SnapshotReason::Conversion { from })?;
+        if format.is_text() {
+            TextStorage::new(&directory, format).initialize()?;
+        }
+
+        let mut copied = 0;
+        for collection in COLLECTIONS {
+            for record in self.raw_records(from, collection).await? {
+                let Some(id) = record.get("id").and_then(Value::as_str) else {
+                    return Err(crate::ProjectError::invalid_record(collection, "Record without an id"));
+                };
+                self.raw_upsert(format, collection, id, &record).await?;
+                copied += 1;
+            }
+        }
+
+        let mut config = self.config();
+        config.storage = format;
+        self.update_config(config).await?;
+        log::info!("Converted {copied} records of project {} from {from:?} to {format:?}", self.id());
+        Ok(copied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factory(id: &str, notes: Option<&str>) -> Value {
+        serde_json::json!({ "id": id, "name": format!("Factory {id}"), "notes": notes, "tags": ["iron", "early"] })
+    }
+
+    /// Copies every record of `collection` the way `Project::convert_storage` does for text formats.
+    fn copy(from: &TextStorage, to: &TextStorage, collection: &str) {
+        for record in from.list(collection).unwrap() {
+            to.put(collection, record["id"].as_str().unwrap(), &record).unwrap();
+        }
+    }
+
+    #[test]
+    fn put_get_find_remove() {
+        for format in [StorageFormat::Json, StorageFormat::Toml] {
+            let directory = tempfile::tempdir().unwrap();
+            let storage = TextStorage::new(directory.path(), format);
+            storage.initialize().unwrap();
+            assert!(directory.path().join(".gitignore").exists());
+
+            storage.put("factories", "b", &factory("b", Some("west"))).unwrap();
+            storage.put("factories", "a", &factory("a", Some("east"))).unwrap();
+            let file = directory.path().join(RECORDS_DIRECTORY).join("factories").join(format!("a.{}", format.extension()));
+            assert!(file.is_file());
+
+            assert_eq!(storage.get("factories", "a").unwrap(), Some(factory("a", Some("east"))));
+            assert_eq!(storage.get("factories", "c").unwrap(), None);
+            assert_eq!(storage.list("factories").unwrap(), vec![factory("a", Some("east")), factory("b", Some("west"))]);
+            assert_eq!(
+                storage.find("factories", &bson::doc! { "notes": "west" }).unwrap(),
+                vec![factory("b", Some("west"))]
+            );
+
+            storage.remove("factories", "a").unwrap();
+            assert_eq!(storage.get("factories", "a").unwrap(), None);
+            assert!(storage.list("notes").unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn reject_invalid_ids() {
+        let directory = tempfile::tempdir().unwrap();
+        let storage = TextStorage::new(directory.path(), StorageFormat::Json);
+        for id in ["", "../a", "a/b", "a\\b", "a.json"] {
+            assert!(storage.put("factories", id, &factory("a", None)).is_err(), "accepted {id:?}");
+        }
+    }
+
+    #[test]
+    fn toml_drops_nulls() {
+        let directory = tempfile::tempdir().unwrap();
+        let storage = TextStorage::new(directory.path(), StorageFormat::Toml);
+        storage.put("factories", "a", &factory("a", None)).unwrap();
+
+        let stored = storage.get("factories", "a").unwrap().unwrap();
+        assert!(stored.get("notes").is_none());
+        assert_eq!(stored["name"], "Factory a");
+        assert_eq!(stored["tags"], serde_json::json!(["iron", "early"]));
+    }
+
+    #[test]
+    fn round_trip_between_formats() {
+        let directory = tempfile::tempdir().unwrap();
+        let records = [factory("a", Some("east")), factory("b", Some("west"))];
+        fs::write(directory.path().join("project.db"), b"db").unwrap();
+
+        let json = TextStorage::new(directory.path(), StorageFormat::Json);
+        json.initialize().unwrap();
+        for record in records.iter() {
+            json.put("factories", record["id"].as_str().unwrap(), record).unwrap();
+        }
+        discard_storage(directory.path(), StorageFormat::Database, StorageFormat::Json).unwrap();
+        assert!(!directory.path().join("project.db").exists());
+
+        let toml = TextStorage::new(directory.path(), StorageFormat::Toml);
+        copy(&json, &toml, "factories");
+        discard_storage(directory.path(), StorageFormat::Json, StorageFormat::Toml).unwrap();
+        assert!(json.list("factories").unwrap().is_empty());
+        assert_eq!(toml.list("factories").unwrap(), records.to_vec());
+
+        let back = TextStorage::new(directory.path(), StorageFormat::Json);
+        copy(&toml, &back, "factories");
+        discard_storage(directory.path(), StorageFormat::Toml, StorageFormat::Json).unwrap();
+        assert!(toml.list("factories").unwrap().is_empty());
+        assert_eq!(back.list("factories").unwrap(), records.to_vec());
+
+        discard_storage(directory.path(), StorageFormat::Json, StorageFormat::Database).unwrap();
+        assert!(!directory.path().join(RECORDS_DIRECTORY).exists());
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, to_string_pretty};
+use specta::Type;
+use tauri::Manager;
+
+use super::{AppStateExt, ProjectConfig, ProjectLock, ProjectsExt};
+
+/// Folder under the projects directory that holds removed projects.
+pub const TRASH_DIRECTORY: &str = ".trash";
+
+/// Written into a trashed project's folder to record when it was removed.
+const TRASH_INFO_FILE: &str = "trashed.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct TrashEntry {
+    /// Name of the entry's folder in the trash, used to restore or purge it.
+    pub entry: String,
+    pub config: ProjectConfig,
+    pub removed: DateTime<Utc>,
+}
+
+/// Reads every entry in `trash`, most recently removed first. Entries are named by their folder, whatever their
+/// info file says.
+fn read_trash(trash: &Path) -> crate::Result<Vec<TrashEntry>> {
+    if !trash.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut results: Vec<TrashEntry> = Vec::new();
+    for folder in fs::read_dir(trash).or_else(|e| Err(crate::Error::from(e)))? {
+        let Ok(dir) = folder else {
+            continue;
+        };
+        let Ok(data) = fs::read(dir.path().join(TRASH_INFO_FILE)) else {
+            continue;
+        };
+        if let Ok(entry) = from_slice::<TrashEntry>(data.as_slice()) {
+            results.push(TrashEntry {
+                entry: dir.file_name().to_string_lossy().to_string(),
+                ..entry
+            });
+        }
+    }
+
+    results.sort_by(|a, b| b.removed.cmp(&a.removed));
+    Ok(results)
+}
+
+/// Deletes one entry of `trash`, or every entry if `entry` is `None`. Only names listed by `read_trash` are
+/// accepted, so `entry` can't point outside the trash.
+fn purge(trash: &Path, entry: Option<String>) -> crate::Result<Vec<String>> {
+    let listed: Vec<String> = read_trash(trash)?.into_iter().map(|trashed| trashed.entry).collect();
+    let entries = match entry {
+        Some(entry) if listed.contains(&entry) => vec![entry],
+        Some(entry) => return Err(crate::ProjectError::trash_entry_not_exists(entry)),
+        None => listed,
+    };
+
+    for entry in entries.iter() {
+        fs::remove_dir_all(trash.join(entry)).or_else(|e| Err(crate::Error::from(e)))?;
+    }
+    Ok(entries)
+}
+
+/// Entries removed more than `retention_days` before `now`. A retention of `0` keeps every entry.
+fn expired(entries: &[TrashEntry], retention_days: u32, now: DateTime<Utc>) -> Vec<String> {
+    if retention_days == 0 {
+        return Vec::new();
+    }
+
+    let cutoff = now - Duration::days(retention_days as i64);
+    entries
+        .iter()
+        .filter(|trashed| trashed.removed < cutoff)
+        .map(|trashed| trashed.entry.clone())
+        .collect()
+}
+
+#[async_trait::async_trait]
+pub trait TrashExt {
+    async fn trash_directory(&self) -> PathBuf;
+    async fn trash_project(&self, id: String) -> crate::Result<TrashEntry>;
+    async fn list_trash(&self) -> crate::Result<Vec<TrashEntry>>;
+    async fn restore_project(&self, entry: String) -> crate::Result<ProjectConfig>;
+    async fn purge_trash(&self, entry: Option<String>) -> crate::Result<Vec<String>>;
+    async fn purge_expired_trash(&self) -> crate::Result<Vec<String>>;
+}
+
+#[async_trait::async_trait]
+impl<T: Manager<tauri::Wry> + Send + Sync> TrashExt for T {
+    async fn trash_directory(&self) -> PathBuf {
+        self.projects_directory().await.join(TRASH_DIRECTORY)
+    }
+
+    /// Moves a project's folder into the trash.
+    async fn trash_project(&self, id: String) -> crate::Result<TrashEntry> {
+        let source = self.projects_directory().await.join(id.clone());
+        let data = fs::read(source.join("project.json")).or(Err(crate::ProjectError::not_exists(id.clone())))?;
+        let config = from_slice::<ProjectConfig>(data.as_slice()).or_else(|e| Err(crate::Error::deserialization(e)))?;
+        ProjectLock::check(&id, &source)?;
+
+        let removed = Utc::now();
+        let trashed = TrashEntry {
+            entry: format!("{}-{}", removed.format("%Y%m%dT%H%M%S"), id),
+            config,
+            removed,
+        };
+        let trash = self.trash_directory().await;
+        fs::create_dir_all(&trash).or_else(|e| Err(crate::Error::from(e)))?;
+
+        let target = trash.join(trashed.entry.clone());
+        fs::rename(&source, &target).or_else(|e| Err(crate::Error::from(e)))?;
+        fs::write(
+            target.join(TRASH_INFO_FILE),
+            to_string_pretty(&trashed).or_else(|e| Err(crate::Error::serialization(e)))?,
+        )
+        .or_else(|e| Err(crate::Error::from(e)))?;
+
+        Ok(trashed)
+    }
+
+    /// Lists trashed projects, most recently removed first.
+    async fn list_trash(&self) -> crate::Result<Vec<TrashEntry>> {
+        read_trash(&self.trash_directory().await)
+    }
+
+    /// Moves a trashed project back into the projects directory under its original id.
+    async fn restore_project(&self, entry: String) -> crate::Result<ProjectConfig> {
+        let trashed = self
+            .list_trash()
+            .await?
+            .into_iter()
+            .find(|existing| existing.entry == entry)
+            .ok_or(crate::ProjectError::trash_entry_not_exists(entry.clone()))?;
+
+        let target = self.projects_directory().await.join(trashed.config.id.clone());
+        if target.exists() {
+            return Err(crate::ProjectError::exists(trashed.config.id));
+        }
+
+        let source = self.trash_directory().await.join(entry);
+        fs::remove_file(source.join(TRASH_INFO_FILE)).or_else(|e| Err(crate::Error::from(e)))?;
+        fs::rename(source, target).or_else(|e| Err(crate::Error::from(e)))?;
+        Ok(trashed.config)
+    }
+
+    /// Permanently deletes one trash entry, or every entry if `entry` is `None`. Returns the purged entries.
+    async fn purge_trash(&self, entry: Option<String>) -> crate::Result<Vec<String>> {
+        purge(&self.trash_directory().await, entry)
+    }
+
+    /// Purges entries older than `AppState::trash_retention_days`. A retention of `0` keeps the trash forever.
+    async fn purge_expired_trash(&self) -> crate::Result<Vec<String>> {
+        let retention = self.app_state().await.trash_retention_days;
+        let trash = self.trash_directory().await;
+
+        let mut purged = Vec::new();
+        for entry in expired(&read_trash(&trash)?, retention, Utc::now()) {
+            purged.extend(purge(&trash, Some(entry))?);
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trash_entry(trash: &Path, id: &str, days_ago: i64) -> TrashEntry {
+        let removed = Utc::now() - Duration::days(days_ago);
+        let trashed = TrashEntry {
+            entry: format!("{}-{id}", removed.format("%Y%m%dT%H%M%S")),
+            config: ProjectConfig::new(id, "1.0"),
+            removed,
+        };
+        fs::create_dir_all(trash.join(&trashed.entry)).unwrap();
+        fs::write(trash.join(&trashed.entry).join(TRASH_INFO_FILE), to_string_pretty(&trashed).unwrap()).unwrap();
+        trashed
+    }
+
+    #[test]
+    fn retention() {
+        let trash = tempfile::tempdir().unwrap();
+        let old = trash_entry(trash.path(), "old", 40);
+        let recent = trash_entry(trash.path(), "recent", 2);
+        let entries = read_trash(trash.path()).unwrap();
+        assert_eq!(
+            entries.iter().map(|trashed| trashed.entry.clone()).collect::<Vec<_>>(),
+            vec![recent.entry.clone(), old.entry.clone()]
+        );
+
+        assert_eq!(expired(&entries, 30, Utc::now()), vec![old.entry.clone()]);
+        assert_eq!(expired(&entries, 1, Utc::now()), vec![recent.entry, old.entry]);
+        assert!(expired(&entries, 0, Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn purge_only_listed_entries() {
+        let projects = tempfile::tempdir().unwrap();
+        let trash = projects.path().join(TRASH_DIRECTORY);
+        let kept = trash_entry(&trash, "kept", 1);
+        let purged = trash_entry(&trash, "purged", 1);
+        fs::create_dir_all(projects.path().join("project")).unwrap();
+        fs::create_dir_all(trash.join("unlisted")).unwrap();
+
+        for entry in ["..", "../project", "unlisted", ""] {
+            assert!(purge(&trash, Some(entry.to_string())).is_err(), "purged {entry:?}");
+        }
+        assert!(projects.path().join("project").exists());
+        assert!(trash.join("unlisted").exists());
+
+        assert_eq!(purge(&trash, Some(purged.entry.clone())).unwrap(), vec![purged.entry.clone()]);
+        assert!(!trash.join(&purged.entry).exists());
+        assert_eq!(purge(&trash, None).unwrap(), vec![kept.entry.clone()]);
+        assert!(trash.join("unlisted").exists());
+    }
+
+    #[test]
+    fn entries_are_named_by_folder() {
+        let trash = tempfile::tempdir().unwrap();
+        let mut trashed = trash_entry(trash.path(), "a", 1);
+        let folder = trashed.entry.clone();
+        trashed.entry = "..".to_string();
+        fs::write(trash.path().join(&folder).join(TRASH_INFO_FILE), to_string_pretty(&trashed).unwrap()).unwrap();
+
+        assert_eq!(read_trash(trash.path()).unwrap()[0].entry, folder);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify_debouncer_mini::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode},
+    DebounceEventResult, Debouncer,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::from_slice;
+use specta::Type;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+use super::{
+    backups::BACKUPS_DIRECTORY,
+    journal::{JOURNAL_FILE, PENDING_JOURNAL_FILE, SESSION_FILE},
+    lock::LOCK_FILE,
+    ProjectConfig,
+};
+
+/// How long the filesystem has to stay quiet before a batch of changes is reported. Long enough to fold a git
+/// checkout or a sync tool's burst of writes into a single batch.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Files the app rewrites while a project is open. Changes to these never count as changes to the project.
+const RUNTIME_FILES: [&str; 4] = [LOCK_FILE, SESSION_FILE, JOURNAL_FILE, PENDING_JOURNAL_FILE];
+
+/// Difference between two scans of the projects directory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Type)]
+pub struct ProjectsChange {
+    pub added: Vec<ProjectConfig>,
+    pub removed: Vec<ProjectConfig>,
+
+    /// Projects whose config or records changed.
+    pub modified: Vec<ProjectConfig>,
+}
+
+impl ProjectsChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Reads the config of every project in `root`, keyed by folder name. Hidden folders (the trash, archives being
+/// imported) are skipped.
+fn scan(root: impl AsRef<Path>) -> HashMap<String, ProjectConfig> {
+    let Ok(folders) = fs::read_dir(root) else {
+        return HashMap::new();
+    };
+
+    folders
+        .filter_map(|folder| folder.ok())
+        .filter(|folder| !folder.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|folder| {
+            let data = fs::read(folder.path().join("project.json")).ok()?;
+            let config = from_slice::<ProjectConfig>(data.as_slice()).ok()?;
+            Some((folder.file_name().to_string_lossy().to_string(), config))
+        })
+        .collect()
+}
+
+/// Watches the projects directory and turns filesystem events into `ProjectsChange`s.
+pub struct ProjectsWatcher {
+    root: PathBuf,
+    known: HashMap<String, ProjectConfig>,
+    events: UnboundedReceiver<Vec<PathBuf>>,
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl ProjectsWatcher {
+    /// Starts watching `root` recursively. The projects found now are the baseline for the first change.
+    pub fn new(root: impl AsRef<Path>) -> crate::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let (sender, events) = unbounded_channel();
+        let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let _ = sender.send(events.into_iter().map(|event| event.path).collect());
+            }
+            Err(e) => log::warn!("Projects watcher error: {e}"),
+        })?;
+        debouncer.watcher().watch(&root, RecursiveMode::Recursive)?;
+
+        Ok(Self {
+            known: scan(&root),
+            root,
+            events,
+            _debouncer: debouncer,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Folder name of the project a changed path belongs to, or `None` if the path is not part of a project's
+    /// contents (hidden folders such as the trash, snapshots and the app's runtime files).
+    fn project_of(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut components = relative.components().map(|component| component.as_os_str().to_string_lossy());
+        let project = components.next()?.to_string();
+        let inner: Vec<String> = components.map(|component| component.to_string()).collect();
+
+        if project.starts_with('.') {
+            return None;
+        }
+        match inner.first().map(String::as_str) {
+            Some(BACKUPS_DIRECTORY) => None,
+            Some(file) if inner.len() == 1 && RUNTIME_FILES.contains(&file) => None,
+            _ if inner.last().is_some_and(|file| file.ends_with(".partial")) => None,
+            _ => Some(project),
+        }
+    }
+
+    /// Waits for the next batch of filesystem events and compares the projects directory with the last scan.
+    /// Returns `None` once the watcher has stopped.
+    pub async fn next(&mut self) -> Option<ProjectsChange> {
+        let paths = self.events.recv().await?;
+        let touched: BTreeSet<String> = paths.iter().filter_map(|path| self.project_of(path)).collect();
+        let current = scan(&self.root);
+
+        let mut change = ProjectsChange::default();
+        for (folder, config) in current.iter() {
+            match self.known.get(folder) {
+                None => change.added.push(config.clone()),
+                Some(previous) if previous != config || touched.contains(folder) => change.modified.push(config.clone()),
+                _ => (),
+            }
+        }
+        for (folder, config) in self.known.iter() {
+            if !current.contains_key(folder) {
+                change.removed.push(config.clone());
+            }
+        }
+
+        self.known = current;
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_project(root: &Path, folder: &str) -> ProjectConfig {
+        let config = ProjectConfig::new(folder, "1.0");
+        fs::create_dir_all(root.join(folder)).unwrap();
+        fs::write(root.join(folder).join("project.json"), serde_json::to_vec(&config).unwrap()).unwrap();
+        config
+    }
+
+    #[test]
+    fn project_of_ignores_runtime_files() {
+        let root = tempfile::tempdir().unwrap();
+        let watcher = ProjectsWatcher::new(root.path()).unwrap();
+        let project_of = |relative: &str| watcher.project_of(&root.path().join(relative));
+
+        assert_eq!(project_of("a/project.json"), Some("a".to_string()));
+        assert_eq!(project_of("a/records/factories/f.json"), Some("a".to_string()));
+        assert_eq!(project_of("a"), Some("a".to_string()));
+
+        assert_eq!(project_of("a/project.lock"), None);
+        assert_eq!(project_of("a/.session"), None);
+        assert_eq!(project_of("a/journal.jsonl"), None);
+        assert_eq!(project_of("a/journal.pending.jsonl"), None);
+        assert_eq!(project_of("a/backups/20250101T000000000-open/project.json"), None);
+        assert_eq!(project_of("a/records/factories/f.partial"), None);
+        assert_eq!(project_of(".trash/20250101T000000-a/project.json"), None);
+        assert_eq!(project_of(".import-a/project.db"), None);
+        assert_eq!(watcher.project_of(Path::new("/elsewhere/a/project.json")), None);
+
+        // A lock file inside records is part of the project, only the top-level one is the app's.
+        assert_eq!(project_of("a/records/project.lock"), Some("a".to_string()));
+    }
+
+    #[test]
+    fn scan_skips_hidden_and_invalid_folders() {
+        let root = tempfile::tempdir().unwrap();
+        let config = write_project(root.path(), "a");
+        write_project(root.path(), ".import-b");
+        fs::create_dir_all(root.path().join("empty")).unwrap();
+        fs::create_dir_all(root.path().join("broken")).unwrap();
+        fs::write(root.path().join("broken").join("project.json"), b"{").unwrap();
+
+        assert_eq!(scan(root.path()), HashMap::from([("a".to_string(), config)]));
+    }
+}
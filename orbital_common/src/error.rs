@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     io,
     path::{Path, PathBuf},
 };
@@ -7,6 +8,53 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use thiserror::Error;
 
+/// Stable, language-independent description of an error, sent alongside the error itself so the
+/// frontend can render a localized message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct ErrorInfo {
+    /// Machine-readable code, e.g. `docs.unknown_locale`. Codes never change once released.
+    pub code: String,
+
+    /// Localization key for the message, e.g. `errors.docs.unknown_locale`.
+    pub key: String,
+
+    /// Values interpolated into the localized message.
+    pub params: BTreeMap<String, String>,
+}
+
+impl ErrorInfo {
+    pub fn new(code: impl AsRef<str>, params: BTreeMap<String, String>) -> Self {
+        Self {
+            code: code.as_ref().to_string(),
+            key: format!("errors.{}", code.as_ref()),
+            params,
+        }
+    }
+}
+
+/// Implemented by every error type that crosses the API boundary.
+pub trait ErrorCode {
+    /// Stable machine-readable code for this error.
+    fn code(&self) -> &'static str;
+
+    /// Parameters for the localized message.
+    fn params(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    fn info(&self) -> ErrorInfo {
+        ErrorInfo::new(self.code(), self.params())
+    }
+}
+
+/// Builds a parameter map from `name => value` pairs.
+#[macro_export]
+macro_rules! error_params {
+    ($($name:literal => $value:expr),* $(,)?) => {
+        ::std::collections::BTreeMap::from([$(($name.to_string(), $value.to_string())),*])
+    };
+}
+
 #[derive(Error, Clone, Debug, Serialize, Deserialize, Type)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum DocsError {
@@ -18,6 +66,21 @@ pub enum DocsError {
 
     #[error("Invalid docs file format: {reason}")]
     InvalidFormat { reason: String },
+
+    #[error("Failed to read docs after {position} bytes: {reason}")]
+    FailedReadStream { position: u32, reason: String },
+
+    #[error("Docs are not valid {encoding} at byte {position}: {reason}")]
+    InvalidEncoding { encoding: String, position: u32, reason: String },
+
+    #[error("Docs are not valid JSON at byte {position}: {reason}")]
+    InvalidJson { position: u32, reason: String },
+
+    #[error("Invalid docs schema: {reason}")]
+    InvalidSchema { reason: String },
+
+    #[error("Docs file doesn't match its schema at {path:?} ({violations} violations): {reason}")]
+    SchemaViolation { path: String, reason: String, violations: u32 },
 }
 
 impl DocsError {
@@ -39,6 +102,110 @@ impl DocsError {
             reason: reason.as_ref().to_string(),
         }
     }
+
+    pub fn failed_read_stream(position: usize, error: io::Error) -> Self {
+        Self::FailedReadStream {
+            position: position as u32,
+            reason: error.to_string(),
+        }
+    }
+
+    pub fn invalid_encoding(encoding: impl AsRef<str>, position: usize, reason: impl AsRef<str>) -> Self {
+        Self::InvalidEncoding {
+            encoding: encoding.as_ref().to_string(),
+            position: position as u32,
+            reason: reason.as_ref().to_string(),
+        }
+    }
+
+    pub fn invalid_json(position: usize, reason: impl AsRef<str>) -> Self {
+        Self::InvalidJson {
+            position: position as u32,
+            reason: reason.as_ref().to_string(),
+        }
+    }
+
+    pub fn invalid_schema(reason: impl AsRef<str>) -> Self {
+        Self::InvalidSchema {
+            reason: reason.as_ref().to_string(),
+        }
+    }
+
+    pub fn schema_violation(path: impl AsRef<str>, reason: impl AsRef<str>, violations: u32) -> Self {
+        Self::SchemaViolation {
+            path: path.as_ref().to_string(),
+            reason: reason.as_ref().to_string(),
+            violations,
+        }
+    }
+}
+
+#[derive(Error, Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlanError {
+    #[error("Invalid production target {target:?}: {reason}")]
+    InvalidTarget { target: String, reason: String },
+
+    #[error("Unknown item: {item}")]
+    UnknownItem { item: String },
+
+    #[error("Unknown recipe: {recipe}")]
+    UnknownRecipe { recipe: String },
+
+    #[error("Recipes for {item} depend on each other in a cycle")]
+    Cycle { item: String },
+}
+
+impl PlanError {
+    pub fn invalid_target(target: impl AsRef<str>, reason: impl AsRef<str>) -> Self {
+        Self::InvalidTarget {
+            target: target.as_ref().to_string(),
+            reason: reason.as_ref().to_string(),
+        }
+    }
+
+    pub fn unknown_item(item: impl AsRef<str>) -> Self {
+        Self::UnknownItem {
+            item: item.as_ref().to_string(),
+        }
+    }
+
+    pub fn unknown_recipe(recipe: impl AsRef<str>) -> Self {
+        Self::UnknownRecipe {
+            recipe: recipe.as_ref().to_string(),
+        }
+    }
+
+    pub fn cycle(item: impl AsRef<str>) -> Self {
+        Self::Cycle {
+            item: item.as_ref().to_string(),
+        }
+    }
+}
+
+#[derive(Error, Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportError {
+    #[error("Failed to write export to {path:?}: {reason}")]
+    FailedWrite { path: PathBuf, reason: String },
+
+    #[error("Database error: {reason}")]
+    Database { reason: String },
+}
+
+impl ExportError {
+    pub fn failed_write(path: impl AsRef<Path>, error: io::Error) -> Self {
+        Self::FailedWrite {
+            path: path.as_ref().to_path_buf(),
+            reason: error.to_string(),
+        }
+    }
+
+    pub fn database(reason: impl ToString) -> Self {
+        Self::Database {
+            reason: reason.to_string(),
+        }
+    }
 }
 
 #[derive(Error, Clone, Debug, Serialize, Deserialize, Type)]
@@ -49,6 +216,109 @@ pub enum CommonError {
         #[serde(flatten)]
         error: DocsError 
     },
+
+    #[error(transparent)]
+    Plan {
+        #[serde(flatten)]
+        error: PlanError,
+    },
+
+    #[error(transparent)]
+    Export {
+        #[serde(flatten)]
+        error: ExportError,
+    },
+}
+
+impl ErrorCode for DocsError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownLocale { .. } => "docs.unknown_locale",
+            Self::FailedRead { .. } => "docs.failed_read",
+            Self::InvalidFormat { .. } => "docs.invalid_format",
+            Self::FailedReadStream { .. } => "docs.failed_read_stream",
+            Self::InvalidEncoding { .. } => "docs.invalid_encoding",
+            Self::InvalidJson { .. } => "docs.invalid_json",
+            Self::InvalidSchema { .. } => "docs.invalid_schema",
+            Self::SchemaViolation { .. } => "docs.schema_violation",
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::UnknownLocale { locale } => error_params! { "locale" => locale },
+            Self::FailedRead { path, reason } => {
+                error_params! { "path" => path.display(), "reason" => reason }
+            }
+            Self::InvalidFormat { reason } | Self::InvalidSchema { reason } => error_params! { "reason" => reason },
+            Self::FailedReadStream { position, reason } | Self::InvalidJson { position, reason } => {
+                error_params! { "position" => position, "reason" => reason }
+            }
+            Self::InvalidEncoding { encoding, position, reason } => {
+                error_params! { "encoding" => encoding, "position" => position, "reason" => reason }
+            }
+            Self::SchemaViolation { path, reason, violations } => {
+                error_params! { "path" => path, "reason" => reason, "violations" => violations }
+            }
+        }
+    }
+}
+
+impl ErrorCode for PlanError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidTarget { .. } => "plan.invalid_target",
+            Self::UnknownItem { .. } => "plan.unknown_item",
+            Self::UnknownRecipe { .. } => "plan.unknown_recipe",
+            Self::Cycle { .. } => "plan.cycle",
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::InvalidTarget { target, reason } => {
+                error_params! { "target" => target, "reason" => reason }
+            }
+            Self::UnknownItem { item } | Self::Cycle { item } => error_params! { "item" => item },
+            Self::UnknownRecipe { recipe } => error_params! { "recipe" => recipe },
+        }
+    }
+}
+
+impl ErrorCode for ExportError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::FailedWrite { .. } => "export.failed_write",
+            Self::Database { .. } => "export.database",
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::FailedWrite { path, reason } => {
+                error_params! { "path" => path.display(), "reason" => reason }
+            }
+            Self::Database { reason } => error_params! { "reason" => reason },
+        }
+    }
+}
+
+impl ErrorCode for CommonError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Docs { error } => error.code(),
+            Self::Plan { error } => error.code(),
+            Self::Export { error } => error.code(),
+        }
+    }
+
+    fn params(&self) -> BTreeMap<String, String> {
+        match self {
+            Self::Docs { error } => error.params(),
+            Self::Plan { error } => error.params(),
+            Self::Export { error } => error.params(),
+        }
+    }
 }
 
 impl From<DocsError> for CommonError {
@@ -57,4 +327,140 @@ impl From<DocsError> for CommonError {
     }
 }
 
+impl From<PlanError> for CommonError {
+    fn from(value: PlanError) -> Self {
+        Self::Plan { error: value }
+    }
+}
+
+impl From<ExportError> for CommonError {
+    fn from(value: ExportError) -> Self {
+        Self::Export { error: value }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CommonError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docs_error_info() {
+        let error = CommonError::from(DocsError::unknown_locale("xx-XX"));
+        let info = error.info();
+
+        assert_eq!(info.code, "docs.unknown_locale");
+        assert_eq!(info.key, "errors.docs.unknown_locale");
+        assert_eq!(info.params.get("locale").map(String::as_str), Some("xx-XX"));
+    }
+
+    /// One value of every error variant.
+    fn every_error() -> Vec<CommonError> {
+        let docs = [
+            DocsError::unknown_locale("xx-XX"),
+            DocsError::failed_read("docs.json", io::Error::other("denied")),
+            DocsError::invalid_format("not an array"),
+            DocsError::failed_read_stream(12, io::Error::other("closed")),
+            DocsError::invalid_encoding("UTF-16LE", 3, "odd length"),
+            DocsError::invalid_json(7, "expected value"),
+            DocsError::invalid_schema("bad schema"),
+            DocsError::schema_violation("/0/Classes", "missing ClassName", 2),
+        ];
+        let plan = [
+            PlanError::invalid_target("Desc_IronIngot_C", "rate must be positive"),
+            PlanError::unknown_item("Desc_Missing_C"),
+            PlanError::unknown_recipe("Recipe_Missing_C"),
+            PlanError::cycle("Desc_Water_C"),
+        ];
+        let export = [
+            ExportError::failed_write("export.db", io::Error::other("denied")),
+            ExportError::database("locked"),
+        ];
+
+        docs.into_iter()
+            .map(CommonError::from)
+            .chain(plan.into_iter().map(CommonError::from))
+            .chain(export.into_iter().map(CommonError::from))
+            .collect()
+    }
+
+    /// Category and position of an error's variant. The matches are exhaustive, so adding a variant doesn't compile
+    /// until it is listed here, and `every_variant_is_sampled` fails until `every_error` samples it.
+    fn variant(error: &CommonError) -> (&'static str, usize) {
+        match error {
+            CommonError::Docs { error } => (
+                "docs",
+                match error {
+                    DocsError::UnknownLocale { .. } => 0,
+                    DocsError::FailedRead { .. } => 1,
+                    DocsError::InvalidFormat { .. } => 2,
+                    DocsError::FailedReadStream { .. } => 3,
+                    DocsError::InvalidEncoding { .. } => 4,
+                    DocsError::InvalidJson { .. } => 5,
+                    DocsError::InvalidSchema { .. } => 6,
+                    DocsError::SchemaViolation { .. } => 7,
+                },
+            ),
+            CommonError::Plan { error } => (
+                "plan",
+                match error {
+                    PlanError::InvalidTarget { .. } => 0,
+                    PlanError::UnknownItem { .. } => 1,
+                    PlanError::UnknownRecipe { .. } => 2,
+                    PlanError::Cycle { .. } => 3,
+                },
+            ),
+            CommonError::Export { error } => (
+                "export",
+                match error {
+                    ExportError::FailedWrite { .. } => 0,
+                    ExportError::Database { .. } => 1,
+                },
+            ),
+        }
+    }
+
+    #[test]
+    fn every_variant_is_sampled() {
+        let mut sampled: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for error in every_error() {
+            let (category, index) = variant(&error);
+            sampled.entry(category).or_default().push(index);
+        }
+
+        for (category, mut indices) in sampled {
+            indices.sort();
+            assert_eq!(indices, (0..indices.len()).collect::<Vec<_>>(), "{category} variants are missing or sampled twice");
+        }
+    }
+
+    #[test]
+    fn error_codes_are_unique() {
+        let errors = every_error();
+        let codes: Vec<&str> = errors.iter().map(|error| error.code()).collect();
+
+        for (i, code) in codes.iter().enumerate() {
+            assert!(!codes[i + 1..].contains(code), "duplicate code {code}");
+            assert!(code.starts_with(&format!("{}.", variant(&errors[i]).0)), "code {code} is outside its category");
+        }
+    }
+
+    #[test]
+    fn error_codes_are_localized() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../orbital/src/lang/en.json");
+        let messages: serde_json::Value = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+
+        for error in every_error() {
+            let info = error.info();
+            let pointer = format!("/{}", info.key.replace('.', "/"));
+            let message = messages.pointer(&pointer).and_then(|message| message.as_str());
+            let Some(message) = message else {
+                panic!("{} has no message in en.json", info.key);
+            };
+            for placeholder in message.split("{{").skip(1).filter_map(|rest| rest.split_once("}}")) {
+                assert!(info.params.contains_key(placeholder.0), "{} has no {} param", info.key, placeholder.0);
+            }
+        }
+    }
+}
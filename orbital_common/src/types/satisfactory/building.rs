@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use super::{Coercion, NormalizedString};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct BuildingFuelType {
     #[serde(alias = "mFuelClass")]
     pub primary_resource: NormalizedString,
@@ -21,7 +22,7 @@ pub struct BuildingFuelType {
     pub byproduct_amount: Option<Coercion>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct BuildingItem {
     #[serde(alias = "ClassName")]
     pub id: NormalizedString,
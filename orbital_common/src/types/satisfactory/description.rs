@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use super::{Coercion, NormalizedString, AssetReference};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DescriptionType {
     #[serde(alias = "RF_LIQUID")]
@@ -19,7 +20,7 @@ pub enum DescriptionType {
     Building,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DescriptionStackSize {
     #[serde(alias = "SS_FLUID")] // Liquid (no stack)
@@ -42,7 +43,7 @@ pub enum DescriptionStackSize {
     Huge,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DescriptionGasType {
     #[serde(alias = "GT_NORMAL")] // Normal or N/A
@@ -52,7 +53,7 @@ pub enum DescriptionGasType {
     Energy
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Type, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DescriptionEquipmentSlot {
     #[serde(alias = "ES_ARMS")]
@@ -71,7 +72,7 @@ pub enum DescriptionEquipmentSlot {
     Body,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct DescriptionItem {
     #[serde(alias = "ClassName")]
     pub id: NormalizedString,
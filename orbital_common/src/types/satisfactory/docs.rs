@@ -0,0 +1,211 @@
+use std::{char::decode_utf16, io::Read};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+use crate::DocsError;
+
+/// Number of leading bytes inspected to detect a docs file's encoding when it has no byte order mark.
+const SNIFF_LENGTH: usize = 512;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DocsEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl DocsEncoding {
+    /// Detects the encoding of `bytes`, returning it with the length of its byte order mark (0 if there is none).
+    ///
+    /// Without a byte order mark, the docs are assumed to start with mostly ASCII JSON, which in UTF-16 puts a zero
+    /// byte in every code unit: after the character in little endian, before it in big endian.
+    pub fn detect(bytes: &[u8]) -> (Self, usize) {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => return (Self::Utf8, 3),
+            [0xFF, 0xFE, ..] => return (Self::Utf16Le, 2),
+            [0xFE, 0xFF, ..] => return (Self::Utf16Be, 2),
+            _ => (),
+        }
+
+        let sample = &bytes[..bytes.len().min(SNIFF_LENGTH)];
+        let zeros = |parity: usize| {
+            sample
+                .iter()
+                .enumerate()
+                .filter(|(index, byte)| index % 2 == parity && **byte == 0)
+                .count()
+        };
+        let (even, odd) = (zeros(0), zeros(1));
+        if even == 0 && odd == 0 {
+            (Self::Utf8, 0)
+        } else if odd >= even {
+            (Self::Utf16Le, 0)
+        } else {
+            (Self::Utf16Be, 0)
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+        }
+    }
+}
+
+/// Text decoded from a docs file, with what's needed to map positions in it back to the original bytes.
+struct Decoded {
+    text: String,
+    encoding: DocsEncoding,
+    bom: usize,
+}
+
+impl Decoded {
+    fn new(bytes: &[u8]) -> crate::Result<Self> {
+        let (encoding, bom) = DocsEncoding::detect(bytes);
+        let body = &bytes[bom..];
+        let text = match encoding {
+            DocsEncoding::Utf8 => std::str::from_utf8(body)
+                .map_err(|e| DocsError::invalid_encoding(encoding.label(), bom + e.valid_up_to(), e.to_string()))?
+                .to_string(),
+            DocsEncoding::Utf16Le | DocsEncoding::Utf16Be => {
+                if !body.len().is_multiple_of(2) {
+                    return Err(DocsError::invalid_encoding(
+                        encoding.label(),
+                        bytes.len() - 1,
+                        "Truncated code unit at the end of the file",
+                    )
+                    .into());
+                }
+
+                let units = body.chunks_exact(2).map(|unit| match encoding {
+                    DocsEncoding::Utf16Be => u16::from_be_bytes([unit[0], unit[1]]),
+                    _ => u16::from_le_bytes([unit[0], unit[1]]),
+                });
+                let mut text = String::with_capacity(body.len() / 2);
+                let mut position = bom;
+                for decoded in decode_utf16(units) {
+                    match decoded {
+                        Ok(character) => {
+                            text.push(character);
+                            position += 2 * character.len_utf16();
+                        }
+                        Err(e) => {
+                            return Err(DocsError::invalid_encoding(encoding.label(), position, e.to_string()).into());
+                        }
+                    }
+                }
+                text
+            }
+        };
+
+        Ok(Self { text, encoding, bom })
+    }
+
+    /// Byte position in the original input of a 1-based line and column of the decoded text, as reported by
+    /// serde_json.
+    fn position(&self, line: usize, column: usize) -> usize {
+        let line_start: usize = self
+            .text
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum();
+        let mut offset = (line_start + column.saturating_sub(1)).min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        match self.encoding {
+            DocsEncoding::Utf8 => self.bom + offset,
+            DocsEncoding::Utf16Le | DocsEncoding::Utf16Be => self.bom + 2 * self.text[..offset].encode_utf16().count(),
+        }
+    }
+}
+
+/// Parses docs held in memory, in any encoding `DocsEncoding::detect` recognizes.
+pub fn parse_docs(bytes: &[u8]) -> crate::Result<Value> {
+    let decoded = Decoded::new(bytes)?;
+    serde_json::from_str::<Value>(&decoded.text).map_err(|e| {
+        DocsError::invalid_json(decoded.position(e.line(), e.column()), e.to_string()).into()
+    })
+}
+
+/// Parses docs from any reader, such as a zip entry or stdin. The reader is consumed to the end.
+pub fn parse_docs_reader(mut reader: impl Read) -> crate::Result<Value> {
+    let mut bytes: Vec<u8> = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| DocsError::failed_read_stream(bytes.len(), e))?;
+    parse_docs(bytes.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::json;
+
+    use super::*;
+
+    const DOCS: &str = "[{\"NativeClass\": \"Desc\", \"Classes\": [{\"mDisplayName\": \"Béton\"}]}]";
+
+    fn encode(text: &str, encoding: DocsEncoding, bom: bool) -> Vec<u8> {
+        let mut bytes = match (encoding, bom) {
+            (DocsEncoding::Utf8, true) => vec![0xEF, 0xBB, 0xBF],
+            (DocsEncoding::Utf16Le, true) => vec![0xFF, 0xFE],
+            (DocsEncoding::Utf16Be, true) => vec![0xFE, 0xFF],
+            _ => Vec::new(),
+        };
+        match encoding {
+            DocsEncoding::Utf8 => bytes.extend(text.as_bytes()),
+            DocsEncoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+            DocsEncoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        }
+        bytes
+    }
+
+    #[test]
+    fn parse_any_encoding() -> crate::Result<()> {
+        let expected = json!([{"NativeClass": "Desc", "Classes": [{"mDisplayName": "Béton"}]}]);
+        for encoding in [DocsEncoding::Utf8, DocsEncoding::Utf16Le, DocsEncoding::Utf16Be] {
+            for bom in [false, true] {
+                let bytes = encode(DOCS, encoding, bom);
+                assert_eq!(DocsEncoding::detect(&bytes).0, encoding, "{encoding:?}, bom: {bom}");
+                assert_eq!(parse_docs(&bytes)?, expected, "{encoding:?}, bom: {bom}");
+                assert_eq!(parse_docs_reader(Cursor::new(bytes))?, expected, "{encoding:?}, bom: {bom}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn report_byte_positions() {
+        let position = |result: crate::Result<Value>| match result {
+            Err(crate::CommonError::Docs {
+                error: DocsError::InvalidEncoding { position, .. } | DocsError::InvalidJson { position, .. },
+            }) => Some(position),
+            _ => None,
+        };
+
+        let mut truncated = encode(DOCS, DocsEncoding::Utf16Le, true);
+        truncated.pop();
+        assert_eq!(position(parse_docs(&truncated)), Some(truncated.len() as u32 - 1));
+
+        let mut invalid = encode("[1, 2]", DocsEncoding::Utf8, false);
+        invalid.insert(3, 0xFF);
+        assert_eq!(position(parse_docs(&invalid)), Some(3));
+
+        let mut surrogate = encode("[1, 2]", DocsEncoding::Utf16Be, true);
+        surrogate.splice(6..6, [0xDC, 0x00]);
+        assert_eq!(position(parse_docs(&surrogate)), Some(6));
+
+        // The stray `x` is the 15th character: 2 bytes of BOM and 14 code units before it
+        assert_eq!(position(parse_docs(&encode("[1,\n 2,\n 3,   x]", DocsEncoding::Utf16Le, true))), Some(2 + 2 * 14));
+        assert_eq!(position(parse_docs(&encode("[\"é\", x]", DocsEncoding::Utf8, false))), Some(7));
+    }
+}
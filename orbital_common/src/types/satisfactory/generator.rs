@@ -1,10 +1,11 @@
 use crate::types::satisfactory::{BuildingItem, DescriptionItem, RecipeItem, ResearchItem};
 use convert_case::{Case, Casing};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct Generated {
     pub research: HashMap<String, ResearchItem>,
     pub descriptions: HashMap<String, DescriptionItem>,
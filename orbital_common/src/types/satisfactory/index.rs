@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use super::{OrbitalData, RecipeItem};
+
+/// Lookup tables over `OrbitalData::recipes`, keyed by the ids the recipes reference.
+#[derive(Debug, Clone, Default)]
+pub struct DataIndex {
+    pub recipes_by_product: HashMap<String, Vec<String>>,
+    pub recipes_by_ingredient: HashMap<String, Vec<String>>,
+    pub recipes_by_machine: HashMap<String, Vec<String>>,
+}
+
+impl DataIndex {
+    pub fn build(data: &OrbitalData) -> Self {
+        let mut index = Self::default();
+        for (id, recipe) in data.recipes.iter() {
+            for product in recipe.product.as_ref().iter().flatten() {
+                index
+                    .recipes_by_product
+                    .entry(product.item.id().to_string())
+                    .or_default()
+                    .push(id.clone());
+            }
+            for ingredient in recipe.ingredients.as_ref().iter().flatten() {
+                index
+                    .recipes_by_ingredient
+                    .entry(ingredient.item.id().to_string())
+                    .or_default()
+                    .push(id.clone());
+            }
+            for machine in recipe.machine.as_ref().iter().flatten() {
+                index
+                    .recipes_by_machine
+                    .entry(machine.id().to_string())
+                    .or_default()
+                    .push(id.clone());
+            }
+        }
+
+        for ids in index
+            .recipes_by_product
+            .values_mut()
+            .chain(index.recipes_by_ingredient.values_mut())
+            .chain(index.recipes_by_machine.values_mut())
+        {
+            ids.sort();
+        }
+        index
+    }
+
+    fn resolve(data: &OrbitalData, ids: Option<&Vec<String>>) -> Vec<RecipeItem> {
+        ids.into_iter()
+            .flatten()
+            .filter_map(|id| data.recipes.get(id).cloned())
+            .collect()
+    }
+
+    /// Recipes that have `item` as one of their products.
+    pub fn recipes_for(&self, data: &OrbitalData, item: impl AsRef<str>) -> Vec<RecipeItem> {
+        Self::resolve(data, self.recipes_by_product.get(item.as_ref()))
+    }
+
+    /// Recipes that consume `item` as one of their ingredients.
+    pub fn recipes_using(&self, data: &OrbitalData, item: impl AsRef<str>) -> Vec<RecipeItem> {
+        Self::resolve(data, self.recipes_by_ingredient.get(item.as_ref()))
+    }
+
+    /// Recipes that can be produced in `machine`.
+    pub fn recipes_in(&self, data: &OrbitalData, machine: impl AsRef<str>) -> Vec<RecipeItem> {
+        Self::resolve(data, self.recipes_by_machine.get(machine.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample_data() -> OrbitalData {
+        serde_json::from_value(json!({
+            "research": {},
+            "descriptions": {},
+            "buildables": {},
+            "recipes": {
+                "RecipeIronPlateC": {
+                    "id": "RecipeIronPlateC",
+                    "display_name": "Iron Plate",
+                    "ingredients": [{"item": "DescIronIngotC'", "amount": 3}],
+                    "product": [{"item": "DescIronPlateC'", "amount": 2}],
+                    "duration": 6,
+                    "machine": ["BuildConstructorMk1C"]
+                },
+                "RecipeIronRodC": {
+                    "id": "RecipeIronRodC",
+                    "display_name": "Iron Rod",
+                    "ingredients": [{"item": "DescIronIngotC'", "amount": 1}],
+                    "product": [{"item": "DescIronRodC'", "amount": 1}],
+                    "duration": 4,
+                    "machine": ["BuildConstructorMk1C"]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn recipes_for_product() {
+        let data = sample_data();
+        let index = DataIndex::build(&data);
+        let recipes = index.recipes_for(&data, "DescIronPlateC");
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].display_name, "Iron Plate");
+    }
+
+    #[test]
+    fn recipes_using_ingredient() {
+        let data = sample_data();
+        let index = DataIndex::build(&data);
+        assert_eq!(index.recipes_using(&data, "DescIronIngotC").len(), 2);
+        assert_eq!(index.recipes_in(&data, "BuildConstructorMk1C").len(), 2);
+        assert!(index.recipes_for(&data, "DescIronIngotC").is_empty());
+    }
+}
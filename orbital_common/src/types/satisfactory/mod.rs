@@ -1,8 +1,15 @@
 mod building;
 mod description;
+mod docs;
 mod generator;
+mod index;
+mod planner;
 mod recipe;
 mod research;
+mod schema;
+mod search;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod uestring;
 mod utility;
 
@@ -13,14 +20,22 @@ pub use description::{
     DescriptionEquipmentSlot, DescriptionGasType, DescriptionItem, DescriptionStackSize,
     DescriptionType,
 };
+pub use docs::{DocsEncoding, parse_docs, parse_docs_reader};
 pub use generator::{Generated, Generator};
-pub use recipe::RecipeItem;
+pub use index::DataIndex;
+pub use planner::{ItemRate, PlanOptions, PlanStep, PlanTarget, Planner, ProductionPlan};
+pub use recipe::{ItemReference, RecipeItem};
 pub use research::{ResearchItem, ResearchType};
+pub use schema::{DOCS_SCHEMA_FILE, docs_schema, validate_docs, validate_docs_against};
+pub use search::{SearchFilters, SearchIndex, SearchResult};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{ExportSummary, export_sqlite, write_sqlite};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 pub use utility::{ClassReference, Coercion, IconPath, NormalizedString, AssetReference, parse_docs_json};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, JsonSchema)]
 pub struct OrbitalData {
     pub research: HashMap<String, ResearchItem>,
     pub descriptions: HashMap<String, DescriptionItem>,
@@ -38,7 +53,65 @@ pub enum OrbitalItem {
     Recipe(RecipeItem),
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OrbitalItemKind {
+    Research,
+    Description,
+    Buildable,
+    Recipe,
+}
+
+impl OrbitalItem {
+    pub fn kind(&self) -> OrbitalItemKind {
+        match self {
+            OrbitalItem::Research(_) => OrbitalItemKind::Research,
+            OrbitalItem::Description(_) => OrbitalItemKind::Description,
+            OrbitalItem::Buildable(_) => OrbitalItemKind::Buildable,
+            OrbitalItem::Recipe(_) => OrbitalItemKind::Recipe,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            OrbitalItem::Research(item) => item.id.as_ref(),
+            OrbitalItem::Description(item) => item.id.as_ref(),
+            OrbitalItem::Buildable(item) => item.id.as_ref(),
+            OrbitalItem::Recipe(item) => item.id.as_ref(),
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            OrbitalItem::Research(item) => item.display_name.as_str(),
+            OrbitalItem::Description(item) => item.display_name.as_str(),
+            OrbitalItem::Buildable(item) => item.display_name.as_str(),
+            OrbitalItem::Recipe(item) => item.display_name.as_str(),
+        }
+    }
+}
+
+impl From<Generated> for OrbitalData {
+    fn from(value: Generated) -> Self {
+        Self {
+            research: value.research,
+            descriptions: value.descriptions,
+            buildables: value.buildables,
+            recipes: value.recipes,
+        }
+    }
+}
+
 impl OrbitalData {
+    pub fn list_by_kind(&self, kind: OrbitalItemKind) -> Vec<OrbitalItem> {
+        match kind {
+            OrbitalItemKind::Research => self.research.values().cloned().map(OrbitalItem::Research).collect(),
+            OrbitalItemKind::Description => self.descriptions.values().cloned().map(OrbitalItem::Description).collect(),
+            OrbitalItemKind::Buildable => self.buildables.values().cloned().map(OrbitalItem::Buildable).collect(),
+            OrbitalItemKind::Recipe => self.recipes.values().cloned().map(OrbitalItem::Recipe).collect(),
+        }
+    }
+
     pub fn get_id(&self, id: String) -> Option<OrbitalItem> {
         if let Some(item) = self.research.get(&id) {
             return Some(OrbitalItem::Research(item.clone()));
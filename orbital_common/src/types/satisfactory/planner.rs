@@ -0,0 +1,496 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::PlanError;
+
+use super::{BuildingItem, DataIndex, DescriptionType, ItemReference, OrbitalData, RecipeItem};
+
+/// Workbenches and build guns. Recipes are only planned in real production buildings.
+const MANUAL_MACHINES: [&str; 6] = [
+    "BpWorkBenchComponentC",
+    "BpWorkshopComponentC",
+    "BpBuildGunC",
+    "FgBuildGun",
+    "FgBuildableAutomatedWorkBench",
+    "BuildAutomatedWorkBenchC",
+];
+
+/// Buildings whose recipes are never picked by default, since they turn one raw resource into another or only
+/// (un)package fluids. They can still be chosen through `PlanOptions::recipes`.
+const NON_DEFAULT_MACHINES: [&str; 2] = ["BuildConverterC", "BuildPackagerC"];
+
+/// Fluid amounts are given in liters in the docs, and planned in cubic meters.
+const FLUID_SCALE: f32 = 1000.0;
+
+/// A requested output rate, parsed from `ITEM:RATE[/UNIT]` (e.g. `Computer:10/min`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+pub struct PlanTarget {
+    /// Item id or display name
+    pub item: String,
+
+    /// Items per minute
+    pub rate: f32,
+}
+
+impl FromStr for PlanTarget {
+    type Err = PlanError;
+
+    /// Accepts `s`, `sec`, `min`, `h` and `hr` as units. A rate without a unit is per minute.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (item, rate) = value
+            .rsplit_once(':')
+            .ok_or(PlanError::invalid_target(value, "Expected ITEM:RATE"))?;
+        let (amount, unit) = rate.split_once('/').unwrap_or((rate, "min"));
+        let amount = amount
+            .trim()
+            .parse::<f32>()
+            .or(Err(PlanError::invalid_target(value, "Rate is not a number")))?;
+        let per_minute = match unit.trim().to_lowercase().as_str() {
+            "s" | "sec" => amount * 60.0,
+            "m" | "min" => amount,
+            "h" | "hr" => amount / 60.0,
+            other => return Err(PlanError::invalid_target(value, format!("Unknown unit {other:?}"))),
+        };
+
+        if item.trim().is_empty() {
+            return Err(PlanError::invalid_target(value, "Missing item"));
+        }
+        if !per_minute.is_finite() || per_minute <= 0.0 {
+            return Err(PlanError::invalid_target(value, "Rate must be positive"));
+        }
+
+        Ok(Self {
+            item: item.trim().to_string(),
+            rate: per_minute,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Type)]
+pub struct PlanOptions {
+    /// Recipe to make an item with, keyed by item id. Takes precedence over the default choice.
+    #[serde(default)]
+    pub recipes: HashMap<String, String>,
+
+    /// Fall back to alternate recipes for items that no standard recipe makes
+    #[serde(default)]
+    pub alternates: bool,
+
+    /// Item ids to treat as raw inputs even though a recipe produces them
+    #[serde(default)]
+    pub raw: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+pub struct ItemRate {
+    pub item: String,
+    pub display_name: String,
+
+    /// Items (or cubic meters of fluid) per minute
+    pub rate: f32,
+}
+
+/// Machines running one recipe.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+pub struct PlanStep {
+    pub recipe: String,
+    pub recipe_name: String,
+    pub machine: Option<String>,
+    pub machine_name: Option<String>,
+
+    /// Machines needed at 100% clock speed. Fractional counts mean the last machine is underclocked.
+    pub machines: f32,
+
+    /// Power draw in MW at 100% clock speed
+    pub power: f32,
+    pub inputs: Vec<ItemRate>,
+    pub outputs: Vec<ItemRate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+pub struct ProductionPlan {
+    pub targets: Vec<ItemRate>,
+
+    /// Steps ordered from raw inputs towards the targets
+    pub steps: Vec<PlanStep>,
+    pub raw_inputs: Vec<ItemRate>,
+
+    /// Secondary products of the chosen recipes. They are not fed back into the plan.
+    pub byproducts: Vec<ItemRate>,
+    pub power: f32,
+}
+
+/// Works out the machines and raw inputs needed to produce a set of targets, picking one recipe per item.
+#[derive(Debug, Clone)]
+pub struct Planner<'a> {
+    data: &'a OrbitalData,
+    index: DataIndex,
+    options: PlanOptions,
+}
+
+impl<'a> Planner<'a> {
+    pub fn new(data: &'a OrbitalData, options: PlanOptions) -> Self {
+        Self {
+            data,
+            index: DataIndex::build(data),
+            options,
+        }
+    }
+
+    /// Finds an item by id, or by display name ignoring case.
+    pub fn resolve_item(&self, item: impl AsRef<str>) -> Result<String, PlanError> {
+        let item = item.as_ref().trim();
+        if self.data.descriptions.contains_key(item) {
+            return Ok(item.to_string());
+        }
+
+        let mut matches: Vec<&String> = self
+            .data
+            .descriptions
+            .iter()
+            .filter(|(_, description)| description.display_name.eq_ignore_ascii_case(item))
+            .map(|(id, _)| id)
+            .collect();
+        matches.sort();
+        matches
+            .first()
+            .map(|id| id.to_string())
+            .ok_or(PlanError::unknown_item(item))
+    }
+
+    fn display_name(&self, item: &str) -> String {
+        self.data
+            .descriptions
+            .get(item)
+            .map_or(item.to_string(), |description| description.display_name.clone())
+    }
+
+    fn scale(&self, item: &str) -> f32 {
+        match self.data.descriptions.get(item).map(|description| &description.description_type) {
+            Some(DescriptionType::Liquid | DescriptionType::Gas) => FLUID_SCALE,
+            _ => 1.0,
+        }
+    }
+
+    fn amount(&self, reference: &ItemReference) -> f32 {
+        reference.amount.as_f32().unwrap_or(0.0) / self.scale(reference.item.id())
+    }
+
+    fn machine_for(&self, recipe: &RecipeItem) -> Option<&'a BuildingItem> {
+        recipe
+            .machine
+            .as_ref()
+            .iter()
+            .flatten()
+            .filter(|machine| !MANUAL_MACHINES.contains(&machine.id()))
+            .find_map(|machine| self.data.buildables.get(machine.id()))
+    }
+
+    /// The recipe used to make `item`, or `None` if it is a raw input.
+    pub fn recipe_for(&self, item: &str) -> Result<Option<RecipeItem>, PlanError> {
+        if self.options.raw.iter().any(|raw| raw == item) {
+            return Ok(None);
+        }
+        if let Some(recipe) = self.options.recipes.get(item) {
+            return self
+                .data
+                .recipes
+                .get(recipe)
+                .cloned()
+                .map(Some)
+                .ok_or(PlanError::unknown_recipe(recipe));
+        }
+
+        let name = self.display_name(item);
+        let mut candidates: Vec<RecipeItem> = self
+            .index
+            .recipes_for(self.data, item)
+            .into_iter()
+            .filter(|recipe| {
+                recipe
+                    .product
+                    .as_ref()
+                    .iter()
+                    .flatten()
+                    .next()
+                    .is_some_and(|primary| primary.item.id() == item)
+            })
+            .filter(|recipe| {
+                self.machine_for(recipe)
+                    .is_some_and(|machine| !NON_DEFAULT_MACHINES.contains(&machine.id.as_ref()))
+            })
+            .filter(|recipe| self.options.alternates || !is_alternate(recipe))
+            .collect();
+
+        candidates.sort_by_key(|recipe| (is_alternate(recipe), recipe.display_name != name, recipe.id.as_ref().to_string()));
+        Ok(candidates.into_iter().next())
+    }
+
+    /// Orders every item needed for `targets` so that each item comes before the items it is made from.
+    fn order(&self, targets: &[String], recipes: &mut HashMap<String, Option<RecipeItem>>) -> Result<Vec<String>, PlanError> {
+        fn visit(
+            planner: &Planner,
+            item: &str,
+            recipes: &mut HashMap<String, Option<RecipeItem>>,
+            visiting: &mut HashSet<String>,
+            done: &mut HashSet<String>,
+            order: &mut Vec<String>,
+        ) -> Result<(), PlanError> {
+            if done.contains(item) {
+                return Ok(());
+            }
+            if !visiting.insert(item.to_string()) {
+                return Err(PlanError::cycle(planner.display_name(item)));
+            }
+
+            if !recipes.contains_key(item) {
+                recipes.insert(item.to_string(), planner.recipe_for(item)?);
+            }
+            let ingredients: Vec<String> = recipes[item]
+                .iter()
+                .flat_map(|recipe| recipe.ingredients.as_ref().iter().flatten())
+                .map(|ingredient| ingredient.item.id().to_string())
+                .collect();
+            for ingredient in ingredients {
+                visit(planner, &ingredient, recipes, visiting, done, order)?;
+            }
+
+            visiting.remove(item);
+            done.insert(item.to_string());
+            order.push(item.to_string());
+            Ok(())
+        }
+
+        let (mut visiting, mut done, mut order) = (HashSet::new(), HashSet::new(), Vec::new());
+        for target in targets {
+            visit(self, target, recipes, &mut visiting, &mut done, &mut order)?;
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    fn rates(&self, totals: Vec<(String, f32)>) -> Vec<ItemRate> {
+        totals
+            .into_iter()
+            .filter(|(_, rate)| *rate > 0.0)
+            .map(|(item, rate)| ItemRate {
+                display_name: self.display_name(&item),
+                item,
+                rate,
+            })
+            .collect()
+    }
+
+    pub fn plan(&self, targets: &[PlanTarget]) -> crate::Result<ProductionPlan> {
+        let mut demand: HashMap<String, f32> = HashMap::new();
+        let mut requested: Vec<(String, f32)> = Vec::new();
+        for target in targets {
+            let item = self.resolve_item(&target.item)?;
+            *demand.entry(item.clone()).or_default() += target.rate;
+            match requested.iter_mut().find(|(existing, _)| existing == &item) {
+                Some((_, rate)) => *rate += target.rate,
+                None => requested.push((item, target.rate)),
+            }
+        }
+
+        let mut recipes: HashMap<String, Option<RecipeItem>> = HashMap::new();
+        let order = self.order(&requested.iter().map(|(item, _)| item.clone()).collect::<Vec<_>>(), &mut recipes)?;
+
+        let mut steps: Vec<PlanStep> = Vec::new();
+        let mut raw: Vec<(String, f32)> = Vec::new();
+        let mut byproducts: Vec<(String, f32)> = Vec::new();
+        for item in order {
+            let rate = demand.get(&item).copied().unwrap_or(0.0);
+            let Some(recipe) = recipes.get(&item).cloned().flatten() else {
+                raw.push((item, rate));
+                continue;
+            };
+
+            let products = recipe.product.as_ref().clone().unwrap_or_default();
+            let ingredients = recipe.ingredients.as_ref().clone().unwrap_or_default();
+            let per_cycle = products
+                .iter()
+                .find(|product| product.item.id() == item)
+                .map_or(0.0, |product| self.amount(product));
+            let duration = recipe.duration.as_f32().unwrap_or(0.0);
+            if per_cycle <= 0.0 || duration <= 0.0 {
+                raw.push((item, rate));
+                continue;
+            }
+
+            let cycles = rate / per_cycle;
+            let machine = self.machine_for(&recipe);
+            let machines = cycles * duration / 60.0;
+            let inputs: Vec<(String, f32)> = ingredients
+                .iter()
+                .map(|ingredient| (ingredient.item.id().to_string(), self.amount(ingredient) * cycles))
+                .collect();
+            let outputs: Vec<(String, f32)> = products
+                .iter()
+                .map(|product| (product.item.id().to_string(), self.amount(product) * cycles))
+                .collect();
+
+            for (input, amount) in inputs.iter() {
+                *demand.entry(input.clone()).or_default() += amount;
+            }
+            for (output, amount) in outputs.iter().filter(|(output, _)| output != &item) {
+                match byproducts.iter_mut().find(|(existing, _)| existing == output) {
+                    Some((_, total)) => *total += amount,
+                    None => byproducts.push((output.clone(), *amount)),
+                }
+            }
+
+            let power = machine
+                .and_then(|machine| machine.power_consumption.as_ref())
+                .and_then(|power| power.as_f32())
+                .unwrap_or(0.0)
+                * machines;
+            steps.push(PlanStep {
+                recipe: recipe.id.as_ref().to_string(),
+                recipe_name: recipe.display_name.clone(),
+                machine: machine.map(|machine| machine.id.as_ref().to_string()),
+                machine_name: machine.map(|machine| machine.display_name.clone()),
+                machines,
+                power,
+                inputs: self.rates(inputs),
+                outputs: self.rates(outputs),
+            });
+        }
+
+        steps.reverse();
+        raw.reverse();
+        Ok(ProductionPlan {
+            targets: self.rates(requested),
+            power: steps.iter().map(|step| step.power).sum(),
+            steps,
+            raw_inputs: self.rates(raw),
+            byproducts: self.rates(byproducts),
+        })
+    }
+}
+
+fn is_alternate(recipe: &RecipeItem) -> bool {
+    recipe.display_name.starts_with("Alternate")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample_data() -> OrbitalData {
+        let recipe = |id: &str, name: &str, ingredients: serde_json::Value, product: serde_json::Value, duration: u32, machine: &str| {
+            json!({
+                "id": id,
+                "display_name": name,
+                "ingredients": ingredients,
+                "product": product,
+                "duration": duration,
+                "machine": [machine, "BpWorkBenchComponentC"]
+            })
+        };
+        let building = |id: &str, name: &str, power: u32| {
+            json!({"id": id, "display_name": name, "description": "", "power_consumption": power})
+        };
+        let item = |id: &str, name: &str| {
+            json!({"id": id, "display_name": name, "description": "", "description_type": "RF_SOLID"})
+        };
+
+        serde_json::from_value(json!({
+            "research": {},
+            "descriptions": {
+                "DescOreIronC": item("DescOreIronC", "Iron Ore"),
+                "DescIronIngotC": item("DescIronIngotC", "Iron Ingot"),
+                "DescIronPlateC": item("DescIronPlateC", "Iron Plate"),
+                "DescIronRodC": item("DescIronRodC", "Iron Rod"),
+                "DescIronScrewC": item("DescIronScrewC", "Screw"),
+                "DescIronPlateReinforcedC": item("DescIronPlateReinforcedC", "Reinforced Iron Plate")
+            },
+            "buildables": {
+                "BuildSmelterMk1C": building("BuildSmelterMk1C", "Smelter", 4),
+                "BuildConstructorMk1C": building("BuildConstructorMk1C", "Constructor", 4),
+                "BuildAssemblerMk1C": building("BuildAssemblerMk1C", "Assembler", 15)
+            },
+            "recipes": {
+                "RecipeIngotIronC": recipe("RecipeIngotIronC", "Iron Ingot",
+                    json!([{"item": "DescOreIronC'", "amount": 1}]), json!([{"item": "DescIronIngotC'", "amount": 1}]), 2, "BuildSmelterMk1C"),
+                "RecipeIronPlateC": recipe("RecipeIronPlateC", "Iron Plate",
+                    json!([{"item": "DescIronIngotC'", "amount": 3}]), json!([{"item": "DescIronPlateC'", "amount": 2}]), 6, "BuildConstructorMk1C"),
+                "RecipeIronRodC": recipe("RecipeIronRodC", "Iron Rod",
+                    json!([{"item": "DescIronIngotC'", "amount": 1}]), json!([{"item": "DescIronRodC'", "amount": 1}]), 4, "BuildConstructorMk1C"),
+                "RecipeScrewC": recipe("RecipeScrewC", "Screw",
+                    json!([{"item": "DescIronRodC'", "amount": 1}]), json!([{"item": "DescIronScrewC'", "amount": 4}]), 6, "BuildConstructorMk1C"),
+                "RecipeAlternateCastScrewC": recipe("RecipeAlternateCastScrewC", "Alternate: Cast Screw",
+                    json!([{"item": "DescIronIngotC'", "amount": 5}]), json!([{"item": "DescIronScrewC'", "amount": 20}]), 24, "BuildConstructorMk1C"),
+                "RecipeIronPlateReinforcedC": recipe("RecipeIronPlateReinforcedC", "Reinforced Iron Plate",
+                    json!([{"item": "DescIronPlateC'", "amount": 6}, {"item": "DescIronScrewC'", "amount": 12}]),
+                    json!([{"item": "DescIronPlateReinforcedC'", "amount": 1}]), 12, "BuildAssemblerMk1C")
+            }
+        }))
+        .unwrap()
+    }
+
+    fn rate_of(rates: &[ItemRate], item: &str) -> f32 {
+        rates.iter().find(|rate| rate.item == item).map_or(0.0, |rate| rate.rate)
+    }
+
+    #[test]
+    fn parse_targets() {
+        assert_eq!(
+            "Computer:10/min".parse::<PlanTarget>().unwrap(),
+            PlanTarget { item: String::from("Computer"), rate: 10.0 }
+        );
+        assert_eq!("Screw:2/s".parse::<PlanTarget>().unwrap().rate, 120.0);
+        assert_eq!("Screw:30".parse::<PlanTarget>().unwrap().rate, 30.0);
+        assert!("Screw".parse::<PlanTarget>().is_err());
+        assert!("Screw:-1/min".parse::<PlanTarget>().is_err());
+        assert!("Screw:1/week".parse::<PlanTarget>().is_err());
+    }
+
+    #[test]
+    fn plan_reinforced_plates() -> crate::Result<()> {
+        let data = sample_data();
+        let planner = Planner::new(&data, PlanOptions::default());
+        let plan = planner.plan(&["Reinforced Iron Plate:5/min".parse::<PlanTarget>()?])?;
+
+        let machines = |recipe: &str| plan.steps.iter().find(|step| step.recipe == recipe).map(|step| step.machines);
+        assert_eq!(machines("RecipeIronPlateReinforcedC"), Some(1.0));
+        assert_eq!(machines("RecipeIronPlateC"), Some(1.5));
+        assert_eq!(machines("RecipeScrewC"), Some(1.5));
+        assert_eq!(machines("RecipeIronRodC"), Some(1.0));
+        assert_eq!(machines("RecipeIngotIronC"), Some(2.0));
+        assert_eq!(rate_of(&plan.raw_inputs, "DescOreIronC"), 60.0);
+        assert_eq!(plan.steps.last().map(|step| step.recipe.as_str()), Some("RecipeIronPlateReinforcedC"));
+        assert_eq!(plan.power, 15.0 + 4.0 * (1.5 + 1.5 + 1.0 + 2.0));
+        Ok(())
+    }
+
+    #[test]
+    fn plan_options_pick_recipes() -> crate::Result<()> {
+        let data = sample_data();
+        let mut options = PlanOptions::default();
+        options.recipes.insert(String::from("DescIronScrewC"), String::from("RecipeAlternateCastScrewC"));
+        options.raw.push(String::from("DescIronIngotC"));
+        let plan = Planner::new(&data, options).plan(&["DescIronScrewC:40".parse::<PlanTarget>()?])?;
+
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].recipe, "RecipeAlternateCastScrewC");
+        assert_eq!(rate_of(&plan.raw_inputs, "DescIronIngotC"), 10.0);
+        Ok(())
+    }
+
+    #[test]
+    fn plan_unknown_item() {
+        let data = sample_data();
+        let planner = Planner::new(&data, PlanOptions::default());
+        let result = planner.plan(&["Computer:10/min".parse::<PlanTarget>().unwrap()]);
+        assert!(matches!(result, Err(crate::CommonError::Plan { error: PlanError::UnknownItem { .. } })));
+    }
+}
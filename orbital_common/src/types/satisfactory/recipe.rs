@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use super::{uestring::UE, ClassReference, Coercion, NormalizedString};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct ItemReference {
     #[serde(alias = "ItemClass")]
     pub item: ClassReference,
@@ -12,7 +13,7 @@ pub struct ItemReference {
     pub amount: Coercion
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct RecipeItem {
     #[serde(alias = "ClassName")]
     pub id: NormalizedString,
@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use super::{uestring::UE, ClassReference, Coercion, NormalizedString};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct ScannableObject {
     #[serde(alias = "ItemDescriptor")]
     pub item: ClassReference,
@@ -12,7 +13,7 @@ pub struct ScannableObject {
     pub allowed_scanners: Vec<ClassReference>
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 #[serde(tag = "Class")]
 #[serde(rename_all = "snake_case")]
 #[serde(rename_all_fields = "snake_case")]
@@ -89,7 +90,7 @@ pub enum ResearchUnlock {
     Map {},
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ResearchType {
     #[serde(alias = "EST_MAM")]
@@ -106,7 +107,7 @@ pub enum ResearchType {
     ResourceSink
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct ResearchItemCost {
     #[serde(alias = "ItemClass")]
     pub item: ClassReference,
@@ -115,7 +116,7 @@ pub struct ResearchItemCost {
     pub amount: Coercion,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct ResearchItem {
     #[serde(alias = "ClassName")]
     pub id: NormalizedString,
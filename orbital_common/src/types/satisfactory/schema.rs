@@ -0,0 +1,96 @@
+use schemars::schema_for;
+use serde_json::Value;
+
+use crate::DocsError;
+
+use super::Generated;
+
+/// File name of the schema inside an asset pack, next to `docs.json`.
+pub const DOCS_SCHEMA_FILE: &str = "schema.json";
+
+/// JSON Schema of a generated `docs.json`, derived from the types it deserializes into.
+pub fn docs_schema() -> Value {
+    serde_json::to_value(schema_for!(Generated)).unwrap_or_default()
+}
+
+/// Checks a generated `docs.json` against the schema of this version of the types.
+pub fn validate_docs(docs: &Value) -> crate::Result<()> {
+    validate_docs_against(&docs_schema(), docs)
+}
+
+/// Checks a generated `docs.json` against `schema`, e.g. the one shipped in the same asset pack. Fails with the
+/// first violation found, along with the total number of violations.
+pub fn validate_docs_against(schema: &Value, docs: &Value) -> crate::Result<()> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| DocsError::invalid_schema(e.to_string()))?;
+    let violations: Vec<_> = validator.iter_errors(docs).collect();
+    match violations.first() {
+        None => Ok(()),
+        Some(first) => Err(DocsError::schema_violation(
+            first.instance_path.to_string(),
+            first.to_string(),
+            violations.len() as u32,
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn bundled_schemas_up_to_date() {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("../resources/assets");
+        for pack in fs::read_dir(assets).unwrap().filter_map(|entry| entry.ok()) {
+            let shipped = fs::read(pack.path().join(DOCS_SCHEMA_FILE)).unwrap();
+            assert_eq!(
+                serde_json::from_slice::<Value>(shipped.as_slice()).unwrap(),
+                docs_schema(),
+                "{DOCS_SCHEMA_FILE} in {:?} is out of date",
+                pack.file_name()
+            );
+        }
+    }
+
+    #[test]
+    fn validate_generated() -> crate::Result<()> {
+        let docs = json!({
+            "research": {},
+            "descriptions": {
+                "DescIronPlateC": {
+                    "id": "DescIronPlateC",
+                    "display_name": "Iron Plate",
+                    "description": "",
+                    "description_type": "item",
+                    "icon": {"asset_type": "Texture2D", "asset_path": "/Game/IconDesc_IronPlates_256", "asset_id": null}
+                }
+            },
+            "buildables": {},
+            "recipes": {
+                "RecipeIronPlateC": {
+                    "id": "RecipeIronPlateC",
+                    "display_name": "Iron Plate",
+                    "ingredients": [{"item": "DescIronIngotC'", "amount": 3}],
+                    "product": null,
+                    "duration": 6,
+                    "machine": ["BuildConstructorMk1C"]
+                }
+            }
+        });
+        validate_docs(&docs)?;
+
+        let mut broken = docs.clone();
+        broken["descriptions"]["DescIronPlateC"]["description_type"] = json!("plasma");
+        broken["recipes"]["RecipeIronPlateC"].as_object_mut().unwrap().remove("duration");
+        let result = validate_docs(&broken);
+        assert!(matches!(
+            result,
+            Err(crate::CommonError::Docs { error: DocsError::SchemaViolation { violations: 2, .. } })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::{DescriptionType, OrbitalData, OrbitalItemKind};
+
+const NAME_WEIGHT: f32 = 1.0;
+const ID_WEIGHT: f32 = 0.6;
+const DESCRIPTION_WEIGHT: f32 = 0.3;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct SearchFilters {
+    /// Only return entities of these kinds (all kinds if empty)
+    #[serde(default)]
+    pub kinds: Vec<OrbitalItemKind>,
+
+    /// Only return descriptions of these types (doesn't affect other kinds)
+    #[serde(default)]
+    pub description_types: Vec<DescriptionType>,
+
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+pub struct SearchResult {
+    pub id: String,
+    pub kind: OrbitalItemKind,
+    pub display_name: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+struct SearchEntry {
+    id: String,
+    kind: OrbitalItemKind,
+    display_name: String,
+    description_type: Option<DescriptionType>,
+    name: String,
+    name_terms: Vec<String>,
+    id_terms: Vec<String>,
+    description_terms: Vec<String>,
+}
+
+/// Typo-tolerant search over the display names, ids and descriptions of an `OrbitalData`.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    entries: Vec<SearchEntry>,
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn terms(text: &str) -> Vec<String> {
+    normalize(text).split(' ').filter(|t| !t.is_empty()).map(|t| t.to_string()).collect()
+}
+
+/// Splits a class id like `DescIronPlateC` into `["desc", "iron", "plate", "c"]`.
+fn id_terms(id: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for c in id.chars() {
+        if (c.is_uppercase() || !c.is_alphanumeric()) && !current.is_empty() {
+            result.push(current.to_lowercase());
+            current.clear();
+        }
+        if c.is_alphanumeric() {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        result.push(current.to_lowercase());
+    }
+    result
+}
+
+/// Optimal string alignment distance (Levenshtein plus adjacent transpositions).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            rows[i][j] = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                rows[i][j] = rows[i][j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+fn allowed_typos(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+/// Scores a single query term against a single indexed term (0 if they don't match).
+fn term_score(query: &str, term: &str) -> f32 {
+    if query == term {
+        return 1.0;
+    }
+    if term.starts_with(query) {
+        return 0.8;
+    }
+
+    let allowed = allowed_typos(query);
+    if allowed == 0 {
+        return 0.0;
+    }
+
+    // Also compare against the term's prefix, so typos in partially-typed words still match.
+    let prefix: String = term.chars().take(query.chars().count()).collect();
+    let distance = edit_distance(query, term).min(edit_distance(query, prefix.as_str()) + 1);
+    if distance <= allowed {
+        0.6 - 0.15 * distance as f32
+    } else {
+        0.0
+    }
+}
+
+fn best_score(query: &str, terms: &[String]) -> f32 {
+    terms.iter().map(|t| term_score(query, t)).fold(0.0, f32::max)
+}
+
+impl SearchEntry {
+    fn score(&self, query: &str, query_terms: &[String]) -> f32 {
+        let mut total = 0.0;
+        for term in query_terms {
+            let score = (best_score(term, &self.name_terms) * NAME_WEIGHT)
+                .max(best_score(term, &self.id_terms) * ID_WEIGHT)
+                .max(best_score(term, &self.description_terms) * DESCRIPTION_WEIGHT);
+            if score == 0.0 {
+                return 0.0;
+            }
+            total += score;
+        }
+
+        let mut score = total / query_terms.len() as f32;
+        if self.name == query {
+            score += 1.0;
+        } else if self.name.starts_with(query) {
+            score += 0.5;
+        }
+        score
+    }
+
+    fn matches(&self, filters: &SearchFilters) -> bool {
+        if !filters.kinds.is_empty() && !filters.kinds.contains(&self.kind) {
+            return false;
+        }
+        if !filters.description_types.is_empty() && self.kind == OrbitalItemKind::Description {
+            return self
+                .description_type
+                .as_ref()
+                .is_some_and(|t| filters.description_types.contains(t));
+        }
+        true
+    }
+}
+
+impl SearchIndex {
+    pub fn build(data: &OrbitalData) -> Self {
+        let mut entries: Vec<SearchEntry> = Vec::new();
+        let mut push = |id: &str, kind: OrbitalItemKind, name: &str, description: &str, description_type: Option<DescriptionType>| {
+            entries.push(SearchEntry {
+                id: id.to_string(),
+                kind,
+                display_name: name.to_string(),
+                description_type,
+                name: normalize(name),
+                name_terms: terms(name),
+                id_terms: id_terms(id),
+                description_terms: terms(description),
+            })
+        };
+
+        for item in data.research.values() {
+            push(item.id.as_ref(), OrbitalItemKind::Research, &item.display_name, &item.description, None);
+        }
+        for item in data.descriptions.values() {
+            push(
+                item.id.as_ref(),
+                OrbitalItemKind::Description,
+                &item.display_name,
+                &item.description,
+                Some(item.description_type.clone()),
+            );
+        }
+        for item in data.buildables.values() {
+            push(item.id.as_ref(), OrbitalItemKind::Buildable, &item.display_name, &item.description, None);
+        }
+        for item in data.recipes.values() {
+            push(item.id.as_ref(), OrbitalItemKind::Recipe, &item.display_name, "", None);
+        }
+
+        Self { entries }
+    }
+
+    /// Returns matching entities, best match first.
+    pub fn search(&self, query: impl AsRef<str>, filters: &SearchFilters) -> Vec<SearchResult> {
+        let query = normalize(query.as_ref());
+        let query_terms = terms(query.as_str());
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut results: Vec<SearchResult> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.matches(filters))
+            .filter_map(|entry| {
+                let score = entry.score(query.as_str(), &query_terms);
+                (score > 0.0).then(|| SearchResult {
+                    id: entry.id.clone(),
+                    kind: entry.kind,
+                    display_name: entry.display_name.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.display_name.len().cmp(&b.display_name.len()))
+                .then_with(|| a.display_name.cmp(&b.display_name))
+        });
+        if let Some(limit) = filters.limit {
+            results.truncate(limit as usize);
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample_index() -> SearchIndex {
+        let data: OrbitalData = serde_json::from_value(json!({
+            "research": {},
+            "descriptions": {
+                "DescModularFrameHeavyC": {
+                    "id": "DescModularFrameHeavyC",
+                    "display_name": "Heavy Modular Frame",
+                    "description": "A more robust multipurpose frame.",
+                    "description_type": "RF_SOLID"
+                },
+                "DescModularFrameC": {
+                    "id": "DescModularFrameC",
+                    "display_name": "Modular Frame",
+                    "description": "Multi-purpose building block.",
+                    "description_type": "RF_SOLID"
+                },
+                "DescWaterC": {
+                    "id": "DescWaterC",
+                    "display_name": "Water",
+                    "description": "It's water.",
+                    "description_type": "RF_LIQUID"
+                }
+            },
+            "buildables": {},
+            "recipes": {
+                "RecipeAlternatePureIronIngotC": {
+                    "id": "RecipeAlternatePureIronIngotC",
+                    "display_name": "Alternate: Pure Iron Ingot",
+                    "ingredients": [],
+                    "product": [],
+                    "duration": 12,
+                    "machine": []
+                },
+                "RecipeIngotIronC": {
+                    "id": "RecipeIngotIronC",
+                    "display_name": "Iron Ingot",
+                    "ingredients": [],
+                    "product": [],
+                    "duration": 2,
+                    "machine": []
+                }
+            }
+        }))
+        .unwrap();
+        SearchIndex::build(&data)
+    }
+
+    #[test]
+    fn exact_match_ranks_first() {
+        let results = sample_index().search("Modular Frame", &SearchFilters::default());
+        assert_eq!(results[0].id, "DescModularFrameC");
+        assert_eq!(results[1].id, "DescModularFrameHeavyC");
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        let results = sample_index().search("hevy modlar frame", &SearchFilters::default());
+        assert_eq!(results[0].id, "DescModularFrameHeavyC");
+    }
+
+    #[test]
+    fn matches_prefixes() {
+        let results = sample_index().search("alt pure", &SearchFilters::default());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "RecipeAlternatePureIronIngotC");
+    }
+
+    #[test]
+    fn applies_filters() {
+        let index = sample_index();
+        let recipes = index.search(
+            "iron",
+            &SearchFilters { kinds: vec![OrbitalItemKind::Recipe], ..Default::default() },
+        );
+        assert_eq!(recipes.len(), 2);
+
+        let liquids = index.search(
+            "water",
+            &SearchFilters { description_types: vec![DescriptionType::Item], ..Default::default() },
+        );
+        assert!(liquids.is_empty());
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions() {
+        assert_eq!(edit_distance("frmae", "frame"), 1);
+        assert_eq!(edit_distance("iron", "iron"), 0);
+        assert_eq!(edit_distance("ingot", "ingto"), 1);
+    }
+}
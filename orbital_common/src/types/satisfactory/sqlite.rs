@@ -0,0 +1,488 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use rusqlite::{Connection, Transaction, params};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::ExportError;
+
+use super::{
+    ClassReference, Coercion, Generated, ItemReference, ResearchItem, research::ResearchUnlock,
+};
+
+/// Schema written by `write_sqlite`. Reference columns (`item_id`, `recipe_id`, ...) are foreign keys holding the
+/// resolved `ClassReference` id, and are `NULL` when the reference names something that is not part of the export
+/// (e.g. the build gun as a recipe's machine). `class` always holds the referenced id. Amounts are stored as in
+/// the docs, so fluids are in liters.
+const SCHEMA: &str = "
+CREATE TABLE items (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    item_type TEXT NOT NULL,
+    stack_size TEXT,
+    gas_type TEXT,
+    is_alien INTEGER,
+    energy_value REAL,
+    radioactivity REAL,
+    health_gain REAL,
+    power_consumption REAL,
+    sink_points REAL,
+    icon TEXT
+);
+
+CREATE TABLE buildings (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    power_consumption REAL,
+    power_production REAL,
+    overclockable INTEGER
+);
+
+CREATE TABLE recipes (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    duration REAL,
+    alternate INTEGER NOT NULL
+);
+
+CREATE TABLE recipe_inputs (
+    recipe_id TEXT NOT NULL REFERENCES recipes(id),
+    position INTEGER NOT NULL,
+    item_id TEXT REFERENCES items(id),
+    class TEXT NOT NULL,
+    amount REAL,
+    PRIMARY KEY (recipe_id, position)
+);
+
+CREATE TABLE recipe_outputs (
+    recipe_id TEXT NOT NULL REFERENCES recipes(id),
+    position INTEGER NOT NULL,
+    item_id TEXT REFERENCES items(id),
+    class TEXT NOT NULL,
+    amount REAL,
+    PRIMARY KEY (recipe_id, position)
+);
+
+CREATE TABLE recipe_machines (
+    recipe_id TEXT NOT NULL REFERENCES recipes(id),
+    position INTEGER NOT NULL,
+    building_id TEXT REFERENCES buildings(id),
+    class TEXT NOT NULL,
+    PRIMARY KEY (recipe_id, position)
+);
+
+CREATE TABLE research (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    research_type TEXT NOT NULL,
+    tier INTEGER
+);
+
+CREATE TABLE research_costs (
+    research_id TEXT NOT NULL REFERENCES research(id),
+    position INTEGER NOT NULL,
+    item_id TEXT REFERENCES items(id),
+    class TEXT NOT NULL,
+    amount REAL,
+    PRIMARY KEY (research_id, position)
+);
+
+CREATE TABLE research_unlocks (
+    research_id TEXT NOT NULL REFERENCES research(id),
+    position INTEGER NOT NULL,
+    unlock_type TEXT NOT NULL,
+    recipe_id TEXT REFERENCES recipes(id),
+    schematic_id TEXT REFERENCES research(id),
+    item_id TEXT REFERENCES items(id),
+    class TEXT,
+    amount REAL,
+    PRIMARY KEY (research_id, position)
+);
+
+CREATE INDEX recipe_inputs_item ON recipe_inputs(item_id);
+CREATE INDEX recipe_outputs_item ON recipe_outputs(item_id);
+CREATE INDEX recipe_machines_building ON recipe_machines(building_id);
+CREATE INDEX research_costs_item ON research_costs(item_id);
+CREATE INDEX research_unlocks_recipe ON research_unlocks(recipe_id);
+";
+
+/// Rows written by an export, per table.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Type)]
+pub struct ExportSummary {
+    pub items: u32,
+    pub buildings: u32,
+    pub recipes: u32,
+    pub research: u32,
+
+    /// References whose target is not part of the export, written with a `NULL` foreign key
+    pub unresolved: u32,
+}
+
+/// Writes `generated` to a new SQLite database at `path`, replacing any existing file.
+pub fn export_sqlite(generated: &Generated, path: impl AsRef<Path>) -> crate::Result<ExportSummary> {
+    let path = path.as_ref();
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| ExportError::failed_write(path, e))?;
+    }
+
+    let mut connection = Connection::open(path).map_err(ExportError::database)?;
+    write_sqlite(generated, &mut connection)
+}
+
+/// Creates the export schema in an empty database and fills it from `generated` in a single transaction.
+pub fn write_sqlite(generated: &Generated, connection: &mut Connection) -> crate::Result<ExportSummary> {
+    connection
+        .execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(ExportError::database)?;
+    let transaction = connection.transaction().map_err(ExportError::database)?;
+    transaction.execute_batch(SCHEMA).map_err(ExportError::database)?;
+
+    let summary = Writer::new(generated, &transaction)
+        .write()
+        .map_err(ExportError::database)?;
+    transaction.commit().map_err(ExportError::database)?;
+    Ok(summary)
+}
+
+/// Enum values as they appear in the generated JSON, e.g. `mam_research`.
+fn label<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(str::to_string)
+}
+
+fn number(value: &Option<Coercion>) -> Option<f32> {
+    value.as_ref().and_then(Coercion::as_f32)
+}
+
+fn flag(value: &Option<Coercion>) -> Option<bool> {
+    match value {
+        Some(Coercion::Boolean(value)) => Some(*value),
+        Some(Coercion::Integer(value)) => Some(*value != 0),
+        _ => None,
+    }
+}
+
+fn amounts(references: &Option<Vec<ItemReference>>) -> Vec<(&ClassReference, &Coercion)> {
+    references
+        .iter()
+        .flatten()
+        .map(|reference| (&reference.item, &reference.amount))
+        .collect()
+}
+
+/// Targets of one research unlock, as `(class, amount)` rows. Unlocks without a target produce a single row.
+fn unlock_targets(unlock: &ResearchUnlock) -> Vec<(Option<String>, Option<f32>)> {
+    let classes = |references: &Option<Vec<ClassReference>>| -> Vec<(Option<String>, Option<f32>)> {
+        references
+            .iter()
+            .flatten()
+            .map(|reference| (Some(reference.id().to_string()), None))
+            .collect()
+    };
+
+    let targets = match unlock {
+        ResearchUnlock::Recipe { recipes } | ResearchUnlock::Blueprints { recipes } => classes(recipes.as_ref()),
+        ResearchUnlock::Schematic { schematics } => classes(schematics.as_ref()),
+        ResearchUnlock::ScannableResource { resources } => classes(resources.as_ref()),
+        ResearchUnlock::BoomboxTape { tapes } => classes(tapes.as_ref()),
+        ResearchUnlock::Emote { emotes } => classes(emotes.as_ref()),
+        ResearchUnlock::ScannableObject { resources } => resources
+            .as_ref()
+            .iter()
+            .flatten()
+            .map(|object| (Some(object.item.id().to_string()), None))
+            .collect(),
+        ResearchUnlock::InventorySlot { resources: amount }
+        | ResearchUnlock::ToolSlot { amount }
+        | ResearchUnlock::CentralStorageUpload { amount }
+        | ResearchUnlock::CentralStorageItems { amount }
+        | ResearchUnlock::CentralStorageSlots { amount } => vec![(None, amount.as_f32())],
+        ResearchUnlock::Info {}
+        | ResearchUnlock::ProductionBoost {}
+        | ResearchUnlock::BuildEfficiency {}
+        | ResearchUnlock::Overclocking {}
+        | ResearchUnlock::Map {} => Vec::new(),
+    };
+
+    if targets.is_empty() { vec![(None, None)] } else { targets }
+}
+
+struct Writer<'a> {
+    generated: &'a Generated,
+    transaction: &'a Transaction<'a>,
+    summary: ExportSummary,
+}
+
+impl<'a> Writer<'a> {
+    fn new(generated: &'a Generated, transaction: &'a Transaction<'a>) -> Self {
+        Self {
+            generated,
+            transaction,
+            summary: ExportSummary::default(),
+        }
+    }
+
+    /// Resolves a reference against one of the exported collections, counting references that don't resolve.
+    fn resolve<T>(&mut self, collection: &HashMap<String, T>, class: &str) -> Option<String> {
+        if collection.contains_key(class) {
+            Some(class.to_string())
+        } else {
+            self.summary.unresolved += 1;
+            None
+        }
+    }
+
+    fn write(mut self) -> rusqlite::Result<ExportSummary> {
+        self.write_items()?;
+        self.write_buildings()?;
+        self.write_recipes()?;
+        self.write_research()?;
+        Ok(self.summary)
+    }
+
+    fn write_items(&mut self) -> rusqlite::Result<()> {
+        let mut statement = self.transaction.prepare(
+            "INSERT INTO items (id, display_name, description, item_type, stack_size, gas_type, is_alien, energy_value,
+                radioactivity, health_gain, power_consumption, sink_points, icon)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        )?;
+        for (id, item) in self.generated.descriptions.iter() {
+            statement.execute(params![
+                id,
+                item.display_name,
+                item.description,
+                label(&item.description_type),
+                item.stack_size.as_ref().and_then(label),
+                item.gas_type.as_ref().and_then(label),
+                flag(&item.is_alien),
+                number(&item.energy_value),
+                number(&item.radioactivity),
+                number(&item.health_gain),
+                number(&item.power_consumption),
+                number(&item.resource_sink_points),
+                item.icon.as_ref().and_then(|icon| icon.asset_id.clone()),
+            ])?;
+            self.summary.items += 1;
+        }
+        Ok(())
+    }
+
+    fn write_buildings(&mut self) -> rusqlite::Result<()> {
+        let mut statement = self.transaction.prepare(
+            "INSERT INTO buildings (id, display_name, description, power_consumption, power_production, overclockable)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for (id, building) in self.generated.buildables.iter() {
+            statement.execute(params![
+                id,
+                building.display_name,
+                building.description,
+                number(&building.power_consumption),
+                number(&building.power_production),
+                flag(&building.overclockable),
+            ])?;
+            self.summary.buildings += 1;
+        }
+        Ok(())
+    }
+
+    fn write_amounts(&mut self, table: &str, owner: &str, references: Vec<(&ClassReference, &Coercion)>) -> rusqlite::Result<()> {
+        let generated = self.generated;
+        let owner_column = if table == "research_costs" { "research_id" } else { "recipe_id" };
+        let sql = format!("INSERT INTO {table} ({owner_column}, position, item_id, class, amount) VALUES (?1, ?2, ?3, ?4, ?5)");
+        for (position, (reference, amount)) in references.into_iter().enumerate() {
+            let class = reference.id();
+            let item = self.resolve(&generated.descriptions, class);
+            self.transaction
+                .prepare_cached(&sql)?
+                .execute(params![owner, position, item, class, amount.as_f32()])?;
+        }
+        Ok(())
+    }
+
+    fn write_recipes(&mut self) -> rusqlite::Result<()> {
+        let generated = self.generated;
+        for (id, recipe) in self.generated.recipes.iter() {
+            self.transaction
+                .prepare_cached("INSERT INTO recipes (id, display_name, duration, alternate) VALUES (?1, ?2, ?3, ?4)")?
+                .execute(params![
+                    id,
+                    recipe.display_name,
+                    recipe.duration.as_f32(),
+                    recipe.display_name.starts_with("Alternate"),
+                ])?;
+            self.summary.recipes += 1;
+        }
+
+        for (id, recipe) in self.generated.recipes.iter() {
+            self.write_amounts("recipe_inputs", id, amounts(recipe.ingredients.as_ref()))?;
+            self.write_amounts("recipe_outputs", id, amounts(recipe.product.as_ref()))?;
+            for (position, machine) in recipe.machine.as_ref().iter().flatten().enumerate() {
+                let building = self.resolve(&generated.buildables, machine.id());
+                self.transaction
+                    .prepare_cached(
+                        "INSERT INTO recipe_machines (recipe_id, position, building_id, class) VALUES (?1, ?2, ?3, ?4)",
+                    )?
+                    .execute(params![id, position, building, machine.id()])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_research(&mut self) -> rusqlite::Result<()> {
+        for (id, research) in self.generated.research.iter() {
+            self.transaction
+                .prepare_cached(
+                    "INSERT INTO research (id, display_name, description, research_type, tier) VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?
+                .execute(params![
+                    id,
+                    research.display_name,
+                    research.description,
+                    label(&research.research_type),
+                    number(&research.tier),
+                ])?;
+            self.summary.research += 1;
+        }
+
+        for (id, research) in self.generated.research.iter() {
+            let costs = research
+                .cost
+                .iter()
+                .flat_map(|cost| cost.as_ref().iter().flatten())
+                .map(|cost| (&cost.item, &cost.amount))
+                .collect();
+            self.write_amounts("research_costs", id, costs)?;
+            self.write_unlocks(id, research)?;
+        }
+        Ok(())
+    }
+
+    fn write_unlocks(&mut self, id: &str, research: &ResearchItem) -> rusqlite::Result<()> {
+        let generated = self.generated;
+        let mut position = 0;
+        for unlock in research.unlocks.iter().flatten() {
+            let unlock_type = serde_json::to_value(unlock)
+                .ok()
+                .and_then(|value| value.get("Class").and_then(|class| class.as_str()).map(str::to_string))
+                .unwrap_or_default();
+            for (class, amount) in unlock_targets(unlock) {
+                let (mut recipe, mut schematic, mut item) = (None, None, None);
+                if let Some(class) = class.as_deref() {
+                    match unlock {
+                        ResearchUnlock::Recipe { .. } | ResearchUnlock::Blueprints { .. } => {
+                            recipe = self.resolve(&generated.recipes, class)
+                        }
+                        ResearchUnlock::Schematic { .. } => schematic = self.resolve(&generated.research, class),
+                        ResearchUnlock::ScannableResource { .. } | ResearchUnlock::ScannableObject { .. } => {
+                            item = self.resolve(&generated.descriptions, class)
+                        }
+                        _ => (),
+                    }
+                }
+
+                self.transaction
+                    .prepare_cached(
+                        "INSERT INTO research_unlocks
+                            (research_id, position, unlock_type, recipe_id, schematic_id, item_id, class, amount)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    )?
+                    .execute(params![id, position, unlock_type, recipe, schematic, item, class, amount])?;
+                position += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample_data() -> Generated {
+        serde_json::from_value(json!({
+            "research": {
+                "SchematicTier1C": {
+                    "id": "SchematicTier1C",
+                    "display_name": "Base Building",
+                    "description": "",
+                    "research_type": "milestone",
+                    "cost": [{"item": "DescIronPlateC'", "amount": 10}, {"item": "DescCouponC'", "amount": 1}],
+                    "unlocks": [
+                        {"Class": "recipe", "recipes": ["RecipeIronPlateC'"]},
+                        {"Class": "inventory_slot", "resources": 3},
+                        {"Class": "map"}
+                    ],
+                    "tier": 1
+                }
+            },
+            "descriptions": {
+                "DescIronPlateC": {
+                    "id": "DescIronPlateC",
+                    "display_name": "Iron Plate",
+                    "description": "",
+                    "description_type": "item",
+                    "resource_sink_points": 6
+                },
+                "DescIronIngotC": {
+                    "id": "DescIronIngotC",
+                    "display_name": "Iron Ingot",
+                    "description": "",
+                    "description_type": "item"
+                }
+            },
+            "buildables": {
+                "BuildConstructorMk1C": {
+                    "id": "BuildConstructorMk1C",
+                    "display_name": "Constructor",
+                    "description": "",
+                    "power_consumption": 4
+                }
+            },
+            "recipes": {
+                "RecipeIronPlateC": {
+                    "id": "RecipeIronPlateC",
+                    "display_name": "Iron Plate",
+                    "ingredients": [{"item": "DescIronIngotC'", "amount": 3}],
+                    "product": [{"item": "DescIronPlateC'", "amount": 2}],
+                    "duration": 6,
+                    "machine": ["BuildConstructorMk1C", "BpWorkBenchComponentC"]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn export_sample() -> crate::Result<()> {
+        let mut connection = Connection::open_in_memory().unwrap();
+        let summary = write_sqlite(&sample_data(), &mut connection)?;
+        assert_eq!(
+            summary,
+            ExportSummary {
+                items: 2,
+                buildings: 1,
+                recipes: 1,
+                research: 1,
+                unresolved: 2,
+            }
+        );
+
+        let count = |sql: &str| connection.query_row(sql, [], |row| row.get::<_, u32>(0)).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM recipe_machines WHERE building_id IS NOT NULL"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM research_unlocks"), 3);
+        assert_eq!(
+            count(
+                "SELECT COUNT(*) FROM recipe_inputs JOIN items ON items.id = recipe_inputs.item_id
+                 WHERE items.display_name = 'Iron Ingot'"
+            ),
+            1
+        );
+        assert_eq!(count("SELECT COUNT(*) FROM pragma_foreign_key_check"), 0);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, error::Error, fmt::Debug};
 
 use crate::types::satisfactory::Coercion;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::{Value, json};
 use specta::Type;
@@ -111,7 +112,9 @@ impl UEString {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Type)]
+/// Generated packs hold the parsed value, so the schema is the one of `Option<T>`.
+#[derive(Clone, Debug, PartialEq, Type, JsonSchema)]
+#[schemars(inline)]
 pub struct UE<T: Clone + Debug + Serialize + DeserializeOwned + PartialEq>(Option<T>);
 
 impl<T: Clone + Debug + Serialize + DeserializeOwned + PartialEq> Serialize for UE<T> {
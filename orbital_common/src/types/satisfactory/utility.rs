@@ -1,13 +1,15 @@
 use convert_case::{Case, Casing};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use specta::Type;
-use std::{char::decode_utf16, fs::File, io::Read, path::PathBuf};
-use strip_bom::StripBom;
+use std::{fs, path::PathBuf};
 
 use crate::DocsError;
 
-#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+use super::parse_docs;
+
+#[derive(Serialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 #[serde(untagged)]
 pub enum Coercion {
     Float(f32),
@@ -59,6 +61,17 @@ impl<'de> Deserialize<'de> for Coercion {
     }
 }
 
+impl Coercion {
+    /// The numeric value of a float or integer, regardless of which one the docs used.
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Coercion::Float(v) => Some(*v),
+            Coercion::Integer(v) => Some(*v as f32),
+            _ => None,
+        }
+    }
+}
+
 impl Into<f32> for Coercion {
     fn into(self) -> f32 {
         if let Coercion::Float(v) = self {
@@ -109,7 +122,7 @@ impl Into<String> for Coercion {
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct IconPath(Option<String>);
 
 impl<'de> Deserialize<'de> for IconPath {
@@ -149,7 +162,7 @@ impl AsRef<str> for IconPath {
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct NormalizedString(String);
 
 impl<'de> Deserialize<'de> for NormalizedString {
@@ -174,7 +187,7 @@ impl AsRef<str> for NormalizedString {
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct ClassReference(String);
 
 impl<'de> Deserialize<'de> for ClassReference {
@@ -200,6 +213,13 @@ impl<'de> Deserialize<'de> for ClassReference {
     }
 }
 
+impl ClassReference {
+    /// The referenced class id, without the quotes some docs versions leave behind.
+    pub fn id(&self) -> &str {
+        self.0.trim_end_matches(['\'', '"'])
+    }
+}
+
 impl Into<String> for ClassReference {
     fn into(self) -> String {
         self.0
@@ -212,43 +232,18 @@ impl AsRef<str> for ClassReference {
     }
 }
 
+/// Reads the docs of a locale from the game's docs folder. The file can be in any encoding `parse_docs` supports.
 pub fn parse_docs_json(docs_folder: PathBuf, locale: String) -> crate::Result<Value> {
     let combined_path = docs_folder.join(locale.clone() + ".json");
     if !combined_path.exists() {
         return Err(DocsError::unknown_locale(locale).into());
     }
 
-    let mut file_bytes: Vec<u8> = Vec::new();
-    let file_bytesize = File::open(&combined_path)
-        .or_else(|e| {
-            Err::<File, crate::CommonError>(DocsError::failed_read(combined_path.as_path(), e).into())
-        })?
-        .read_to_end(&mut file_bytes)
-        .or_else(|e| {
-            Err::<usize, crate::CommonError>(DocsError::failed_read(combined_path.as_path(), e).into())
-        })?;
-
-    if file_bytesize % 2 != 0 {
-        return Err(
-            DocsError::invalid_format("File is not UTF-16 encoded/is missing bytes.").into(),
-        );
-    }
-    let iter = (0..(file_bytesize / 2))
-        .map(|i| u16::from_le_bytes([file_bytes[2 * i], file_bytes[2 * i + 1]]));
-
-    let decoded = decode_utf16(iter)
-        .collect::<Result<String, _>>()
-        .or_else(|e| {
-            Err::<String, crate::CommonError>(
-                DocsError::invalid_format(format!("Bad UTF-16 encoding: {:?}", e)).into(),
-            )
-        })?;
-
-    serde_json::from_str::<Value>(&decoded.strip_bom())
-        .or_else(|e| Err(DocsError::invalid_format(format!("Bad JSON data: {:?}", e)).into()))
+    let file_bytes = fs::read(&combined_path).map_err(|e| DocsError::failed_read(combined_path.as_path(), e))?;
+    parse_docs(file_bytes.as_slice())
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq, Type)]
+#[derive(Serialize, Clone, Debug, PartialEq, Type, JsonSchema)]
 pub struct AssetReference {
     pub asset_type: String,
     pub asset_path: String,
@@ -260,7 +255,24 @@ impl<'de> Deserialize<'de> for AssetReference {
     where
         D: serde::Deserializer<'de>,
     {
-        let raw = String::deserialize(deserializer)?;
+        /// Docs exports hold the raw `Type Path` string, while generated packs hold the serialized struct.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Raw(String),
+            Parsed {
+                asset_type: String,
+                asset_path: String,
+                asset_id: Option<String>,
+            },
+        }
+
+        let raw = match Repr::deserialize(deserializer)? {
+            Repr::Raw(raw) => raw,
+            Repr::Parsed { asset_type, asset_path, asset_id } => {
+                return Ok(Self { asset_type, asset_path, asset_id });
+            }
+        };
 
         let asset_id = if !raw.contains("/") && raw.ends_with("256") {
             Some(raw.clone())
@@ -283,3 +295,23 @@ impl<'de> Deserialize<'de> for AssetReference {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value, json, to_value};
+
+    use super::*;
+
+    #[test]
+    fn asset_reference_round_trip() {
+        let parsed: AssetReference = from_value(json!(
+            "Texture2D /Game/FactoryGame/Resource/Parts/IronPlate/UI/IconDesc_IronPlates_256.IconDesc_IronPlates_256"
+        ))
+        .unwrap();
+        assert_eq!(parsed.asset_type, "Texture2D");
+        assert_eq!(parsed.asset_id.as_deref(), Some("IconDesc_IronPlates_256"));
+
+        let reloaded: AssetReference = from_value(to_value(&parsed).unwrap()).unwrap();
+        assert_eq!(reloaded, parsed);
+    }
+}